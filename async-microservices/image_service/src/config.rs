@@ -0,0 +1,84 @@
+use std::env;
+use std::io::{Error, ErrorKind};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+pub enum Backend {
+    Filesystem,
+    Memory,
+}
+
+/*
+    Read from environment variables:
+
+    STORAGE            - `fs` (default) or `memory`
+    FILES_DIR          - where the `fs` backend keeps files, default `./files`
+    RETENTION_TTL      - seconds a file is kept for, unlimited if unset
+    RETENTION_QUOTA    - total bytes kept across all files, unlimited if unset
+    RETENTION_INTERVAL - seconds between retention sweeps, default 60
+    SIGNING_KEY        - when set, downloads require a link signed with this key
+    ADMIN_TOKEN        - bearer token for listing, deleting and minting links, required with
+                         SIGNING_KEY so that links can't be minted by anyone
+
+    Resumable upload sessions are always staged on disk under `FILES_DIR/uploads`.
+*/
+pub struct Config {
+    pub backend: Backend,
+    pub files_dir: PathBuf,
+    pub retention_ttl: Option<Duration>,
+    pub retention_quota: Option<u64>,
+    pub retention_interval: Duration,
+    pub signing_key: Option<String>,
+    pub admin_token: Option<String>,
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self, Error> {
+        let backend = match env::var("STORAGE") {
+            Err(_) => Backend::Filesystem,
+            Ok(ref value) if value == "fs" => Backend::Filesystem,
+            Ok(ref value) if value == "memory" => Backend::Memory,
+            Ok(value) => {
+                let msg = format!("unknown storage backend: {}", value);
+                return Err(Error::new(ErrorKind::InvalidInput, msg));
+            }
+        };
+        let files_dir = env::var("FILES_DIR").unwrap_or_else(|_| "./files".into());
+        let retention_ttl = parse_var("RETENTION_TTL")?.map(Duration::from_secs);
+        let retention_quota = parse_var("RETENTION_QUOTA")?;
+        let retention_interval = parse_var("RETENTION_INTERVAL")?.unwrap_or(60);
+        let signing_key = env::var("SIGNING_KEY").ok();
+        let admin_token = env::var("ADMIN_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty());
+        if signing_key.is_some() && admin_token.is_none() {
+            let msg = "ADMIN_TOKEN must be set along with SIGNING_KEY";
+            return Err(Error::new(ErrorKind::InvalidInput, msg));
+        }
+
+        Ok(Self {
+            backend,
+            files_dir: files_dir.into(),
+            retention_ttl,
+            retention_quota,
+            retention_interval: Duration::from_secs(retention_interval),
+            signing_key,
+            admin_token,
+        })
+    }
+
+    pub fn uploads_dir(&self) -> PathBuf {
+        self.files_dir.join("uploads")
+    }
+}
+
+fn parse_var<T: FromStr>(name: &str) -> Result<Option<T>, Error> {
+    match env::var(name) {
+        Ok(value) => value.parse().map(Some).map_err(|_| {
+            let msg = format!("invalid value for {}: {}", name, value);
+            Error::new(ErrorKind::InvalidInput, msg)
+        }),
+        Err(_) => Ok(None),
+    }
+}
@@ -0,0 +1,93 @@
+use crate::resumable::ResponseFuture;
+use crate::storage::{Stat, Storage};
+use crate::{query_pairs, response_with_code};
+use futures::Future;
+use hyper::header::CONTENT_TYPE;
+use hyper::{Body, Response, StatusCode};
+use std::cmp::min;
+use std::time::UNIX_EPOCH;
+
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 1000;
+
+#[derive(Serialize)]
+struct FileInfo {
+    id: String,
+    size: u64,
+    /// Seconds since the unix epoch
+    modified: u64,
+    filename: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Listing {
+    files: Vec<FileInfo>,
+    next_cursor: Option<String>,
+}
+
+impl From<Stat> for FileInfo {
+    fn from(stat: Stat) -> Self {
+        let modified = stat
+            .modified
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        Self {
+            id: stat.id,
+            size: stat.size,
+            modified,
+            filename: stat.metadata.filename,
+        }
+    }
+}
+
+/// Pages through the files ordered by id, `cursor` is the last id of the previous page
+pub fn list(query: Option<&str>, storage: &dyn Storage) -> ResponseFuture {
+    let mut limit = DEFAULT_LIMIT;
+    let mut cursor = None;
+    for (key, value) in query_pairs(query) {
+        match key {
+            "limit" => match value.parse() {
+                Ok(value) if value > 0 => limit = min(value, MAX_LIMIT),
+                _ => return response_with_code(StatusCode::BAD_REQUEST),
+            },
+            "cursor" => cursor = Some(value.to_owned()),
+            _ => {}
+        }
+    }
+
+    let body = storage.list().map(move |mut files| {
+        files.sort_by(|a, b| a.id.cmp(&b.id));
+        if let Some(cursor) = cursor {
+            files.retain(|stat| stat.id > cursor);
+        }
+        let next_cursor = if files.len() > limit {
+            files.truncate(limit);
+            files.last().map(|stat| stat.id.clone())
+        } else {
+            None
+        };
+        let listing = Listing {
+            files: files.into_iter().map(FileInfo::from).collect(),
+            next_cursor,
+        };
+        let body = serde_json::to_vec(&listing).expect("listing is serializable");
+        Response::builder()
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    });
+    Box::new(body)
+}
+
+pub fn delete(id: &str, storage: &dyn Storage) -> ResponseFuture {
+    let body = storage.delete(id).and_then(|removed| {
+        let status_code = if removed {
+            StatusCode::NO_CONTENT
+        } else {
+            StatusCode::NOT_FOUND
+        };
+        response_with_code(status_code)
+    });
+    Box::new(body)
+}
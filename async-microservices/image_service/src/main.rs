@@ -1,76 +1,203 @@
 #[macro_use]
 extern crate lazy_static;
+extern crate env_logger;
 extern crate futures;
+extern crate hmac;
 extern crate hyper;
 extern crate hyper_staticfile;
+extern crate log;
 extern crate rand;
 extern crate regex;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+extern crate sha2;
 extern crate tokio;
 
+mod config;
+mod files;
+mod multipart;
+mod resumable;
+mod retention;
+mod signing;
+mod storage;
+
+use config::{Backend, Config};
 use futures::{future, Future, Stream};
-use hyper::service::service_fn;
+use hyper::header::{CONTENT_TYPE, WWW_AUTHENTICATE};
+use hyper::server::conn::AddrStream;
+use hyper::service::{make_service_fn, service_fn};
 use hyper::{Body, Method, Request, Response, Server, StatusCode};
-use hyper_staticfile::FileChunkStream;
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
 use regex::Regex;
+use signing::Signer;
 use std::fs;
 use std::io::{Error, ErrorKind};
-use std::path::Path;
-use tokio::fs::File;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use storage::{FileStorage, MemoryStorage, Metadata, Storage};
 
 static INDEX: &[u8] = b"Images Microservice";
 
 lazy_static! {
     static ref DOWNLOAD_FILE: Regex = Regex::new("^/download/(?P<filename>\\w{20})?$").unwrap();
+    static ref FILE: Regex = Regex::new("^/files/(?P<id>\\w{20})(?P<link>/link)?$").unwrap();
+    static ref UPLOAD_SESSION: Regex =
+        Regex::new("^/uploads/(?P<id>\\w{20})(?P<finalize>/finalize)?$").unwrap();
 }
 
-fn main() {
-    let files = Path::new("./files");
-    fs::create_dir(files).ok();
+struct Context {
+    storage: Arc<dyn Storage>,
+    uploads: PathBuf,
+    appending: resumable::Appending,
+    signer: Option<Signer>,
+    admin_token: Option<String>,
+}
+
+impl Context {
+    /// Listing, deleting and minting links are for admins only, once a token is configured
+    fn is_admin(&self, req: &Request<Body>) -> bool {
+        match &self.admin_token {
+            Some(token) => signing::is_bearer(req.headers(), token),
+            None => true,
+        }
+    }
+}
+
+fn main() -> Result<(), Error> {
+    env_logger::init();
+    let config = Config::from_env()?;
+    let storage: Arc<dyn Storage> = match config.backend {
+        Backend::Filesystem => Arc::new(FileStorage::new(&config.files_dir)?),
+        Backend::Memory => Arc::new(MemoryStorage::new()),
+    };
+    let uploads = config.uploads_dir();
+    fs::create_dir_all(&uploads)?;
+    let policy = retention::Policy {
+        ttl: config.retention_ttl,
+        quota: config.retention_quota,
+    };
+    let retention = if policy.ttl.is_some() || policy.quota.is_some() {
+        Some(retention::task(
+            storage.clone(),
+            policy,
+            config.retention_interval,
+        ))
+    } else {
+        None
+    };
+    let signer = config.signing_key.map(|key| Signer::new(key.as_bytes()));
+    let context = Arc::new(Context {
+        storage,
+        uploads,
+        appending: resumable::Appending::default(),
+        signer,
+        admin_token: config.admin_token,
+    });
 
     let addr = ([127, 0, 0, 1], 8080).into();
     let builder = Server::bind(&addr);
 
-    let server = builder.serve(move || service_fn(move |req| microservice_handler(req, &files)));
+    let server = builder.serve(make_service_fn(move |conn: &AddrStream| {
+        let context = context.clone();
+        let remote = conn.remote_addr();
+        service_fn(move |req| microservice_handler(req, &context, remote))
+    }));
     let server = server.map_err(drop);
 
-    hyper::rt::run(server)
+    hyper::rt::run(future::lazy(move || {
+        if let Some(retention) = retention {
+            hyper::rt::spawn(retention);
+        }
+        server
+    }));
+    Ok(())
 }
 
 fn microservice_handler(
     req: Request<Body>,
-    files: &Path,
+    context: &Context,
+    remote: SocketAddr,
 ) -> Box<dyn Future<Item = Response<Body>, Error = std::io::Error> + Send> {
     match (req.method(), req.uri().path().to_owned().as_ref()) {
         (&Method::GET, "/") => Box::new(future::ok(Response::new(INDEX.into()))),
         (&Method::POST, "/upload") => {
-            let name: String = thread_rng().sample_iter(&Alphanumeric).take(20).collect();
-            let mut filepath = files.to_path_buf();
-            filepath.push(&name);
+            let boundary = req
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(multipart::boundary);
+            if let Some(boundary) = boundary {
+                let storage = context.storage.clone();
+                return multipart::upload(req.into_body(), &boundary, storage);
+            }
 
-            let create_file = File::create(filepath);
-            let write = create_file.and_then(|file| {
-                req.into_body().map_err(other).fold(file, |file, chunk| {
-                    tokio::io::write_all(file, chunk).map(|(file, _)| file)
-                })
-            });
+            let name: String = thread_rng().sample_iter(&Alphanumeric).take(20).collect();
+            let data = Box::new(req.into_body().map_err(other));
+            let write = context.storage.put(&name, data, Metadata::default());
 
             let body = write.map(|_| Response::new(name.into()));
 
             Box::new(body)
         }
+        (&Method::GET, "/files") if !context.is_admin(&req) => unauthorized(),
+        (&Method::GET, "/files") => files::list(req.uri().query(), context.storage.as_ref()),
+        (method, path) if path.starts_with("/files/") => {
+            if let Some(cap) = FILE.captures(path) {
+                let id = cap.name("id").unwrap().as_str();
+                match (method, cap.name("link").is_some(), &context.signer) {
+                    (&Method::DELETE, false, _) if !context.is_admin(&req) => unauthorized(),
+                    (&Method::DELETE, false, _) => files::delete(id, context.storage.as_ref()),
+                    (&Method::POST, true, Some(_)) if !context.is_admin(&req) => unauthorized(),
+                    (&Method::POST, true, Some(signer)) => {
+                        signing::mint(id, req.uri().query(), signer, context.storage.as_ref())
+                    }
+                    (&Method::POST, true, None) => response_with_code(StatusCode::NOT_FOUND),
+                    _ => response_with_code(StatusCode::METHOD_NOT_ALLOWED),
+                }
+            } else {
+                response_with_code(StatusCode::NOT_FOUND)
+            }
+        }
+        (&Method::POST, "/uploads") => resumable::create(req, &context.uploads),
+        (method, path) if path.starts_with("/uploads/") => {
+            if let Some(cap) = UPLOAD_SESSION.captures(path) {
+                let id = cap.name("id").unwrap().as_str();
+                match (method, cap.name("finalize").is_some()) {
+                    (&Method::HEAD, false) => resumable::head(id, &context.uploads),
+                    (&Method::PATCH, false) => {
+                        resumable::append(req, id, &context.uploads, &context.appending)
+                    }
+                    (&Method::POST, true) => resumable::finalize(
+                        id,
+                        &context.uploads,
+                        context.storage.clone(),
+                        &context.appending,
+                    ),
+                    _ => response_with_code(StatusCode::METHOD_NOT_ALLOWED),
+                }
+            } else {
+                response_with_code(StatusCode::NOT_FOUND)
+            }
+        }
         (&Method::GET, path) if path.starts_with("/download") => {
             if let Some(cap) = DOWNLOAD_FILE.captures(path) {
                 let filename = cap.name("filename").unwrap().as_str();
-                let mut filepath = files.to_path_buf();
-                filepath.push(filename);
-                let open_file = File::open(filepath);
+                if let Some(signer) = &context.signer {
+                    if !signer.verify(filename, req.uri().query(), remote.ip()) {
+                        return response_with_code(StatusCode::FORBIDDEN);
+                    }
+                }
+                let open_file = context.storage.get(filename);
 
-                let body = open_file.map(|file| {
-                    let chunks = FileChunkStream::new(file);
-                    Response::new(Body::wrap_stream(chunks))
-                });
+                let body = open_file
+                    .map(|chunks| Response::new(Body::wrap_stream(chunks)))
+                    .or_else(|err| match err.kind() {
+                        ErrorKind::NotFound => response_with_code(StatusCode::NOT_FOUND),
+                        _ => Box::new(future::err(err)),
+                    });
 
                 Box::new(body)
             } else {
@@ -91,9 +218,90 @@ fn response_with_code(
     Box::new(future::ok(resp))
 }
 
+fn unauthorized() -> Box<dyn Future<Item = Response<Body>, Error = Error> + Send> {
+    let resp = Response::builder()
+        .status(StatusCode::UNAUTHORIZED)
+        .header(WWW_AUTHENTICATE, "Bearer")
+        .body(Body::empty())
+        .unwrap();
+    Box::new(future::ok(resp))
+}
+
+/// Splits a query string into its `key=value` pairs, without any percent-decoding
+fn query_pairs(query: Option<&str>) -> impl Iterator<Item = (&str, &str)> {
+    query.unwrap_or("").split('&').filter_map(|pair| {
+        let mut parts = pair.splitn(2, '=');
+        Some((parts.next()?, parts.next()?))
+    })
+}
+
 fn other<E>(err: E) -> Error
 where
     E: Into<Box<dyn std::error::Error + Send + Sync>>,
 {
     Error::new(ErrorKind::Other, err)
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use hyper::header::AUTHORIZATION;
+    use tokio::runtime::Runtime;
+
+    pub(crate) const ID: &str = "abcdefghij0123456789";
+    const TOKEN: &str = "secret";
+
+    /// A context over memory storage holding `ID`, with an admin token and optionally a signer
+    pub(crate) fn context(runtime: &mut Runtime, signer: Option<Signer>) -> Context {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let data = Box::new(futures::stream::once(Ok("hello".into())));
+        runtime
+            .block_on(storage.put(ID, data, Metadata::default()))
+            .unwrap();
+        Context {
+            storage,
+            uploads: std::env::temp_dir(),
+            appending: resumable::Appending::default(),
+            signer,
+            admin_token: Some(TOKEN.to_owned()),
+        }
+    }
+
+    pub(crate) fn status(
+        runtime: &mut Runtime,
+        context: &Context,
+        req: Request<Body>,
+        remote: SocketAddr,
+    ) -> StatusCode {
+        let resp = runtime.block_on(microservice_handler(req, context, remote));
+        resp.unwrap().status()
+    }
+
+    #[test]
+    fn delete_is_for_admins() {
+        let mut runtime = Runtime::new().unwrap();
+        let context = context(&mut runtime, None);
+        let remote = ([127, 0, 0, 1], 1234).into();
+        let delete = |token: Option<&str>| {
+            let mut builder = Request::delete(format!("/files/{}", ID));
+            if let Some(token) = token {
+                builder.header(AUTHORIZATION, format!("Bearer {}", token));
+            }
+            builder.body(Body::empty()).unwrap()
+        };
+
+        let unauthorized = StatusCode::UNAUTHORIZED;
+        assert_eq!(
+            status(&mut runtime, &context, delete(None), remote),
+            unauthorized
+        );
+        let req = delete(Some("guess"));
+        assert_eq!(status(&mut runtime, &context, req, remote), unauthorized);
+        assert!(runtime.block_on(context.storage.stat(ID)).is_ok());
+
+        let req = delete(Some(TOKEN));
+        let no_content = StatusCode::NO_CONTENT;
+        assert_eq!(status(&mut runtime, &context, req, remote), no_content);
+        assert!(runtime.block_on(context.storage.stat(ID)).is_err());
+    }
+}
@@ -0,0 +1,480 @@
+use crate::resumable::ResponseFuture;
+use crate::storage::{Metadata, Storage};
+use crate::{other, response_with_code};
+use futures::sync::{mpsc, oneshot};
+use futures::{future, stream, Async, Future, Poll, Sink, Stream};
+use hyper::header::CONTENT_TYPE;
+use hyper::{Body, Chunk, Response, StatusCode};
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use std::io::{Error, ErrorKind};
+use std::sync::{Arc, Mutex};
+
+const MAX_HEADERS_LEN: usize = 8 * 1024;
+
+/*
+    A `multipart/form-data` body is a sequence of parts separated by the boundary:
+
+    --{boundary}\r\n
+    Content-Disposition: form-data; name="file"; filename="cat.png"\r\n
+    \r\n
+    {data}\r\n
+    --{boundary}--\r\n
+
+    `Multipart` turns the body into a stream of events without buffering the part data, so each
+    file can be streamed to storage as it arrives.
+*/
+pub enum Event {
+    Part { filename: Option<String> },
+    Data(Chunk),
+    PartEnd,
+}
+
+enum State {
+    Preamble,
+    Boundary,
+    Headers,
+    Data,
+    Done,
+}
+
+pub struct Multipart<S> {
+    body: S,
+    buffer: Vec<u8>,
+    /// `\r\n--{boundary}`, the first boundary is matched without the leading CRLF
+    delimiter: Vec<u8>,
+    state: State,
+}
+
+impl<S> Multipart<S> {
+    pub fn new(body: S, boundary: &str) -> Self {
+        Self {
+            body,
+            buffer: Vec::new(),
+            delimiter: format!("\r\n--{}", boundary).into_bytes(),
+            state: State::Preamble,
+        }
+    }
+
+    /// Produces the next event from the buffered data, `None` means more data is needed
+    fn next_event(&mut self) -> Result<Option<Event>, Error> {
+        loop {
+            match self.state {
+                State::Preamble => {
+                    let dash_boundary = &self.delimiter[2..];
+                    match find(&self.buffer, dash_boundary) {
+                        Some(pos) => {
+                            self.buffer.drain(..pos + dash_boundary.len());
+                            self.state = State::Boundary;
+                        }
+                        None => {
+                            let keep = dash_boundary.len() - 1;
+                            let skip = self.buffer.len().saturating_sub(keep);
+                            self.buffer.drain(..skip);
+                            return Ok(None);
+                        }
+                    }
+                }
+                State::Boundary => {
+                    if self.buffer.len() < 2 {
+                        return Ok(None);
+                    }
+                    self.state = match &self.buffer[..2] {
+                        b"--" => State::Done,
+                        b"\r\n" => State::Headers,
+                        _ => return Err(invalid("malformed boundary")),
+                    };
+                    self.buffer.drain(..2);
+                }
+                State::Headers => {
+                    // A part without any headers starts with the blank line straight away
+                    let (headers_len, separator_len) = if self.buffer.starts_with(b"\r\n") {
+                        (0, 2)
+                    } else {
+                        match find(&self.buffer, b"\r\n\r\n") {
+                            Some(pos) => (pos, 4),
+                            None if self.buffer.len() > MAX_HEADERS_LEN => {
+                                return Err(invalid("part headers are too long"));
+                            }
+                            None => return Ok(None),
+                        }
+                    };
+                    let filename = {
+                        let headers = std::str::from_utf8(&self.buffer[..headers_len])
+                            .map_err(|_| invalid("part headers aren't valid utf-8"))?;
+                        filename(headers)
+                    };
+                    self.buffer.drain(..headers_len + separator_len);
+                    self.state = State::Data;
+                    return Ok(Some(Event::Part { filename }));
+                }
+                State::Data => {
+                    let available = match find(&self.buffer, &self.delimiter) {
+                        Some(0) => {
+                            self.buffer.drain(..self.delimiter.len());
+                            self.state = State::Boundary;
+                            return Ok(Some(Event::PartEnd));
+                        }
+                        Some(pos) => pos,
+                        // The tail could be the start of a delimiter split across chunks
+                        None => self.buffer.len().saturating_sub(self.delimiter.len() - 1),
+                    };
+                    if available == 0 {
+                        return Ok(None);
+                    }
+                    let data: Vec<u8> = self.buffer.drain(..available).collect();
+                    return Ok(Some(Event::Data(data.into())));
+                }
+                State::Done => return Ok(None),
+            }
+        }
+    }
+}
+
+impl<S> Stream for Multipart<S>
+where
+    S: Stream<Item = Chunk, Error = Error>,
+{
+    type Item = Event;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Event>, Error> {
+        loop {
+            if let Some(event) = self.next_event()? {
+                return Ok(Async::Ready(Some(event)));
+            }
+            if let State::Done = self.state {
+                return Ok(Async::Ready(None));
+            }
+            match futures::try_ready!(self.body.poll()) {
+                Some(chunk) => self.buffer.extend_from_slice(&chunk),
+                None => return Err(invalid("body ended before the closing boundary")),
+            }
+        }
+    }
+}
+
+/// Extracts the boundary from a `multipart/form-data` content type
+pub fn boundary(content_type: &str) -> Option<String> {
+    let media_type = content_type.split(';').next()?.trim();
+    if !media_type.eq_ignore_ascii_case("multipart/form-data") {
+        return None;
+    }
+    param(content_type, "boundary").filter(|boundary| !boundary.is_empty())
+}
+
+/// The filename of a file part, a file input with nothing picked sends an empty one
+fn filename(headers: &str) -> Option<String> {
+    let disposition = headers.split("\r\n").find_map(|line| {
+        let mut parts = line.splitn(2, ':');
+        let name = parts.next()?.trim();
+        if name.eq_ignore_ascii_case("content-disposition") {
+            parts.next()
+        } else {
+            None
+        }
+    })?;
+    param(disposition, "filename").filter(|filename| !filename.is_empty())
+}
+
+/// Finds a `; name=value` parameter of a header value, the value may be a quoted string,
+/// which can contain `;` and backslash escapes
+fn param(header: &str, name: &str) -> Option<String> {
+    let mut chars = header.chars().peekable();
+    // Skips the type, such as `form-data`, parameters follow the first `;`
+    chars.by_ref().find(|&c| c == ';')?;
+    while chars.peek().is_some() {
+        let mut key = String::new();
+        let mut has_value = false;
+        for c in chars.by_ref() {
+            match c {
+                '=' => {
+                    has_value = true;
+                    break;
+                }
+                ';' => break,
+                c => key.push(c),
+            }
+        }
+        if !has_value {
+            continue;
+        }
+        while let Some(&c) = chars.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            chars.next();
+        }
+        let value = if chars.peek() == Some(&'"') {
+            chars.next();
+            let mut value = String::new();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => break,
+                    '\\' => value.extend(chars.next()),
+                    c => value.push(c),
+                }
+            }
+            chars.by_ref().find(|&c| c == ';');
+            value
+        } else {
+            let value: String = chars.by_ref().take_while(|&c| c != ';').collect();
+            value.trim().to_owned()
+        };
+        if key.trim().eq_ignore_ascii_case(name) {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn invalid(msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
+
+type UploadFuture = Box<dyn Future<Item = Upload, Error = Error> + Send>;
+
+#[derive(Default)]
+struct Upload {
+    ids: Vec<String>,
+    current: Option<Part>,
+}
+
+/// `None` marks the end of the part, so storage can tell a complete part from an abandoned one
+struct Part {
+    id: String,
+    sender: mpsc::Sender<Option<Chunk>>,
+    done: oneshot::Receiver<Result<u64, Error>>,
+}
+
+impl Part {
+    fn start(filename: Option<String>, storage: &dyn Storage) -> Self {
+        let id: String = thread_rng().sample_iter(&Alphanumeric).take(20).collect();
+        let (sender, receiver) = mpsc::channel::<Option<Chunk>>(1);
+        let data = receiver
+            .map_err(|_| other("part channel failed"))
+            .chain(stream::once(Err(other("part upload was interrupted"))))
+            .take_while(|chunk| Ok(chunk.is_some()))
+            .map(Option::unwrap);
+
+        let (done_tx, done) = oneshot::channel();
+        let put = storage
+            .put(&id, Box::new(data), Metadata { filename })
+            .then(move |result| {
+                done_tx.send(result).ok();
+                Ok(())
+            });
+        hyper::rt::spawn(put);
+        Self { id, sender, done }
+    }
+
+    fn send(self, chunk: Option<Chunk>) -> impl Future<Item = Self, Error = Error> {
+        let Part { id, sender, done } = self;
+        sender.send(chunk).then(move |result| -> PartFuture {
+            match result {
+                Ok(sender) => Box::new(future::ok(Part { id, sender, done })),
+                // Storage only stops reading when it failed, report its error instead
+                Err(_) => Box::new(
+                    finished(done).and_then(|_| Err(other("storage stopped reading the part"))),
+                ),
+            }
+        })
+    }
+
+    fn finish(self) -> impl Future<Item = String, Error = Error> {
+        self.send(None).and_then(|part| {
+            let Part { id, sender, done } = part;
+            drop(sender);
+            finished(done).map(move |_| id)
+        })
+    }
+}
+
+type PartFuture = Box<dyn Future<Item = Part, Error = Error> + Send>;
+
+/// Streams every file part of the body to storage one after the other, responding with the ids
+/// in the order the parts were sent
+pub fn upload(body: Body, boundary: &str, storage: Arc<dyn Storage>) -> ResponseFuture {
+    let events = Multipart::new(body.map_err(other), boundary);
+    // Every part started, to remove them again when the upload fails, as nobody gets their ids
+    let started = Arc::new(Mutex::new(Vec::new()));
+    let cleanup = (started.clone(), storage.clone());
+    let upload = events.fold(
+        Upload::default(),
+        move |mut upload, event| -> UploadFuture {
+            match (event, upload.current.take()) {
+                // Plain form fields are skipped
+                (Event::Part { filename: None }, _) => Box::new(future::ok(upload)),
+                (Event::Part { filename }, _) => {
+                    let part = Part::start(filename, storage.as_ref());
+                    started.lock().unwrap().push(part.id.clone());
+                    upload.current = Some(part);
+                    Box::new(future::ok(upload))
+                }
+                (Event::Data(chunk), Some(part)) => Box::new(part.send(Some(chunk)).map(|part| {
+                    upload.current = Some(part);
+                    upload
+                })),
+                (Event::PartEnd, Some(part)) => Box::new(part.finish().map(|id| {
+                    upload.ids.push(id);
+                    upload
+                })),
+                (Event::Data(_), None) | (Event::PartEnd, None) => Box::new(future::ok(upload)),
+            }
+        },
+    );
+
+    let body = upload
+        .map(|upload| {
+            let body = serde_json::to_vec(&upload.ids).expect("ids are serializable");
+            Response::builder()
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        })
+        .or_else(move |err| {
+            let (started, storage) = cleanup;
+            let ids = started.lock().unwrap().split_off(0);
+            let deletes = ids.iter().map(|id| storage.delete(id).then(|_| Ok(())));
+            future::join_all(deletes.collect::<Vec<_>>()).then(move |_: Result<_, Error>| match err
+                .kind()
+            {
+                ErrorKind::InvalidData => response_with_code(StatusCode::BAD_REQUEST),
+                _ => Box::new(future::err(err)),
+            })
+        });
+    Box::new(body)
+}
+
+fn finished(done: oneshot::Receiver<Result<u64, Error>>) -> impl Future<Item = u64, Error = Error> {
+    done.map_err(|_| other("part upload was cancelled"))
+        .and_then(|result| result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &[u8] = b"preamble\r\n\
+        --xyz\r\n\
+        Content-Disposition: form-data; name=\"a\"; filename=\"a.txt\"\r\n\
+        Content-Type: text/plain\r\n\
+        \r\n\
+        first\r\n--xy not the delimiter\r\n\
+        --xyz\r\n\
+        \r\n\
+        \r\n\
+        --xyz\r\n\
+        Content-Disposition: form-data; name=\"b\"; filename=\"b.bin\"\r\n\
+        \r\n\
+        second\r\n\
+        --xyz--\r\n\
+        epilogue";
+
+    /// The filename and the data of a part
+    type Part = (Option<String>, Vec<u8>);
+
+    /// Feeds `body` in chunks of `size` bytes, collecting the parts
+    fn parts(body: &[u8], size: usize) -> Result<Vec<Part>, Error> {
+        let chunks: Vec<Chunk> = body.chunks(size).map(|c| c.to_vec().into()).collect();
+        let events = Multipart::new(stream::iter_ok(chunks), "xyz")
+            .collect()
+            .wait()?;
+        let mut parts = Vec::new();
+        let mut open = false;
+        for event in events {
+            match event {
+                Event::Part { filename } => {
+                    assert!(!open, "part started inside a part");
+                    open = true;
+                    parts.push((filename, Vec::new()));
+                }
+                Event::Data(chunk) => {
+                    assert!(open, "data outside of a part");
+                    parts.last_mut().unwrap().1.extend_from_slice(&chunk);
+                }
+                Event::PartEnd => {
+                    assert!(open, "part ended twice");
+                    open = false;
+                }
+            }
+        }
+        assert!(!open, "stream ended inside a part");
+        Ok(parts)
+    }
+
+    #[test]
+    fn parts_split_across_chunks() {
+        let expected = vec![
+            (
+                Some("a.txt".to_owned()),
+                b"first\r\n--xy not the delimiter".to_vec(),
+            ),
+            (None, Vec::new()),
+            (Some("b.bin".to_owned()), b"second".to_vec()),
+        ];
+        // Every chunk size puts the delimiters and header separators at a different offset
+        for size in 1..=BODY.len() {
+            assert_eq!(parts(BODY, size).unwrap(), expected, "chunks of {}", size);
+        }
+    }
+
+    #[test]
+    fn truncated_body_fails() {
+        let end = BODY.len() - b"--\r\nepilogue".len();
+        for &cut in &[0, 10, 30, 80, end - 3, end] {
+            for &size in &[1, 7, BODY.len()] {
+                let err = parts(&BODY[..cut], size).unwrap_err();
+                assert_eq!(err.kind(), ErrorKind::InvalidData, "cut at {}", cut);
+            }
+        }
+        let err = parts(b"--xyz\r\nno blank line", 4).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let err = parts(b"--xyz!!", 4).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn filename_params() {
+        let headers = |disposition: &str| format!("Content-Disposition: {}\r\n", disposition);
+        let parse = |disposition: &str| filename(&headers(disposition));
+        assert_eq!(
+            parse(r#"form-data; name="file"; filename="cat.png""#),
+            Some("cat.png".into())
+        );
+        assert_eq!(
+            parse(r#"form-data; name="a;b"; filename="c;d=e.png"; size=3"#),
+            Some("c;d=e.png".into())
+        );
+        assert_eq!(
+            parse(r#"form-data; flag; filename="say \"hi\".txt""#),
+            Some(r#"say "hi".txt"#.into())
+        );
+        assert_eq!(
+            parse("form-data; FILENAME = plain.txt "),
+            Some("plain.txt".into())
+        );
+        assert_eq!(parse(r#"form-data; name="file"; filename="""#), None);
+        assert_eq!(parse(r#"form-data; name="field""#), None);
+    }
+
+    #[test]
+    fn boundary_params() {
+        assert_eq!(
+            boundary("multipart/form-data; boundary=abc"),
+            Some("abc".into())
+        );
+        assert_eq!(
+            boundary(r#"Multipart/Form-Data; charset=utf-8; boundary="a b""#),
+            Some("a b".into())
+        );
+        assert_eq!(boundary("multipart/mixed; boundary=abc"), None);
+        assert_eq!(boundary("multipart/form-data; boundary="), None);
+    }
+}
@@ -0,0 +1,403 @@
+use crate::storage::{Metadata, Storage};
+use crate::{other, response_with_code};
+use futures::{future, Future, Stream};
+use hyper::header::{HeaderMap, CONTENT_LENGTH, LOCATION};
+use hyper::{Body, Request, Response, StatusCode};
+use hyper_staticfile::FileChunkStream;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use std::collections::HashSet;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::fs::{File, OpenOptions};
+
+/*
+    Resumable uploads, loosely following the tus protocol:
+
+    POST  /uploads              - creates a session, `Upload-Length` is optional
+    HEAD  /uploads/{id}         - reports the current `Upload-Offset`
+    PATCH /uploads/{id}         - appends the body at the given `Upload-Offset`
+    POST  /uploads/{id}/finalize - hands the completed upload over to the storage backend
+
+    All of the session state lives on disk (`{id}.json` + `{id}.part`), the offset is simply
+    the size of the part file, so sessions survive a server restart. Only one PATCH may append
+    to a session at a time, another one arriving meanwhile gets `423 Locked`. Finalizing holds
+    the session the same way, so it never stores a part file that is still being written.
+*/
+
+pub const UPLOAD_OFFSET: &str = "Upload-Offset";
+pub const UPLOAD_LENGTH: &str = "Upload-Length";
+
+pub type ResponseFuture = Box<dyn Future<Item = Response<Body>, Error = Error> + Send>;
+
+#[derive(Serialize, Deserialize)]
+struct Session {
+    length: Option<u64>,
+}
+
+struct State {
+    length: Option<u64>,
+    offset: u64,
+}
+
+/// The sessions with a PATCH or a finalize in flight
+#[derive(Clone, Default)]
+pub struct Appending(Arc<Mutex<HashSet<String>>>);
+
+/// Frees the session for the next request when dropped, however the current one ends
+struct AppendGuard {
+    appending: Appending,
+    id: String,
+}
+
+impl Appending {
+    fn lock(&self, id: &str) -> Option<AppendGuard> {
+        let mut ids = self.0.lock().unwrap();
+        if !ids.insert(id.to_owned()) {
+            return None;
+        }
+        Some(AppendGuard {
+            appending: self.clone(),
+            id: id.to_owned(),
+        })
+    }
+}
+
+impl Drop for AppendGuard {
+    fn drop(&mut self) {
+        self.appending.0.lock().unwrap().remove(&self.id);
+    }
+}
+
+pub fn create(req: Request<Body>, dir: &Path) -> ResponseFuture {
+    let length = match header_u64(req.headers(), UPLOAD_LENGTH) {
+        Ok(length) => length,
+        Err(_) => return response_with_code(StatusCode::BAD_REQUEST),
+    };
+    let meta = match serde_json::to_vec(&Session { length }) {
+        Ok(meta) => meta,
+        Err(err) => return Box::new(future::err(other(err))),
+    };
+
+    let id: String = thread_rng().sample_iter(&Alphanumeric).take(20).collect();
+    let meta_path = dir.join(format!("{}.json", id));
+
+    // The part file is created first, a session only exists once its metadata is written
+    let create_part = File::create(dir.join(format!("{}.part", id)));
+    let write_meta = create_part.and_then(move |_| tokio::fs::write(meta_path, meta));
+
+    let body = write_meta.map(move |_| {
+        let mut resp = state_response(StatusCode::CREATED, &State { length, offset: 0 });
+        let location = format!("/uploads/{}", id);
+        resp.headers_mut()
+            .insert(LOCATION, location.parse().expect("id is alphanumeric"));
+        *resp.body_mut() = id.into();
+        resp
+    });
+    Box::new(body)
+}
+
+pub fn head(id: &str, dir: &Path) -> ResponseFuture {
+    let body = load_state(dir, id).and_then(|state| -> ResponseFuture {
+        match state {
+            Some(state) => Box::new(future::ok(state_response(StatusCode::OK, &state))),
+            None => response_with_code(StatusCode::NOT_FOUND),
+        }
+    });
+    Box::new(body)
+}
+
+pub fn append(req: Request<Body>, id: &str, dir: &Path, appending: &Appending) -> ResponseFuture {
+    let offset = match header_u64(req.headers(), UPLOAD_OFFSET) {
+        Ok(Some(offset)) => offset,
+        _ => return response_with_code(StatusCode::BAD_REQUEST),
+    };
+    let content_length = header_u64(req.headers(), CONTENT_LENGTH.as_str()).unwrap_or(None);
+    let part = dir.join(format!("{}.part", id));
+    let guard = match appending.lock(id) {
+        Some(guard) => guard,
+        None => return response_with_code(StatusCode::LOCKED),
+    };
+
+    let body = load_state(dir, id).and_then(move |state| -> ResponseFuture {
+        let state = match state {
+            Some(state) => state,
+            None => return response_with_code(StatusCode::NOT_FOUND),
+        };
+        if state.offset != offset {
+            return response_with_code(StatusCode::CONFLICT);
+        }
+        if let (Some(length), Some(content_length)) = (state.length, content_length) {
+            if offset + content_length > length {
+                return response_with_code(StatusCode::PAYLOAD_TOO_LARGE);
+            }
+        }
+
+        // Whatever makes it to disk before a dropped connection counts towards the offset. A
+        // chunked body has no length up front, so a chunk that would go past `Upload-Length`
+        // is refused before any of it is written
+        let length = state.length;
+        let open_part = OpenOptions::new().append(true).open(part);
+        let write = open_part.and_then(move |file| {
+            req.into_body()
+                .map_err(other)
+                .fold((file, offset), move |(file, offset), chunk| {
+                    let len = chunk.len() as u64;
+                    match length {
+                        Some(length) if offset + len > length => {
+                            let err = Error::new(ErrorKind::InvalidData, "past Upload-Length");
+                            return future::Either::A(future::err(err));
+                        }
+                        _ => {}
+                    }
+                    let write = tokio::io::write_all(file, chunk);
+                    future::Either::B(write.map(move |(file, _)| (file, offset + len)))
+                })
+        });
+
+        let body = write
+            .map(move |(_, offset)| {
+                drop(guard);
+                state_response(StatusCode::NO_CONTENT, &State { length, offset })
+            })
+            .or_else(|err| match err.kind() {
+                ErrorKind::InvalidData => response_with_code(StatusCode::PAYLOAD_TOO_LARGE),
+                _ => Box::new(future::err(err)),
+            });
+        Box::new(body)
+    });
+    Box::new(body)
+}
+
+pub fn finalize(
+    id: &str,
+    dir: &Path,
+    storage: Arc<dyn Storage>,
+    appending: &Appending,
+) -> ResponseFuture {
+    let part = dir.join(format!("{}.part", id));
+    let meta = dir.join(format!("{}.json", id));
+    let guard = match appending.lock(id) {
+        Some(guard) => guard,
+        None => return response_with_code(StatusCode::LOCKED),
+    };
+    let id = id.to_owned();
+
+    let body = load_state(dir, &id).and_then(move |state| -> ResponseFuture {
+        let state = match state {
+            Some(state) => state,
+            None => return response_with_code(StatusCode::NOT_FOUND),
+        };
+        if let Some(length) = state.length {
+            if length != state.offset {
+                return Box::new(future::ok(state_response(StatusCode::CONFLICT, &state)));
+            }
+        }
+
+        let store = File::open(part.clone()).and_then(move |file| {
+            let chunks = Box::new(FileChunkStream::new(file));
+            storage.put(&id, chunks, Metadata::default()).map(|_| id)
+        });
+        let complete = store
+            .and_then(|id| tokio::fs::remove_file(meta).map(|_| id))
+            .and_then(|id| tokio::fs::remove_file(part).map(|_| id))
+            .map(move |id| {
+                drop(guard);
+                Response::new(id.into())
+            });
+        Box::new(complete)
+    });
+    Box::new(body)
+}
+
+fn load_state(dir: &Path, id: &str) -> impl Future<Item = Option<State>, Error = Error> {
+    let part = dir.join(format!("{}.part", id));
+    tokio::fs::read(dir.join(format!("{}.json", id)))
+        .and_then(|meta| serde_json::from_slice::<Session>(&meta).map_err(other))
+        .and_then(move |session| {
+            tokio::fs::metadata(part).map(move |metadata| State {
+                length: session.length,
+                offset: metadata.len(),
+            })
+        })
+        .map(Some)
+        .or_else(|err| match err.kind() {
+            ErrorKind::NotFound => Ok(None),
+            _ => Err(err),
+        })
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Result<Option<u64>, Error> {
+    match headers.get(name) {
+        Some(value) => value
+            .to_str()
+            .map_err(other)?
+            .parse()
+            .map(Some)
+            .map_err(other),
+        None => Ok(None),
+    }
+}
+
+fn state_response(status_code: StatusCode, state: &State) -> Response<Body> {
+    let mut builder = Response::builder();
+    builder
+        .status(status_code)
+        .header(UPLOAD_OFFSET, state.offset);
+    if let Some(length) = state.length {
+        builder.header(UPLOAD_LENGTH, length);
+    }
+    builder.body(Body::empty()).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::tests::TempDir;
+    use crate::storage::MemoryStorage;
+    use tokio::runtime::Runtime;
+
+    struct Uploads {
+        runtime: Runtime,
+        dir: TempDir,
+        storage: Arc<dyn Storage>,
+        appending: Appending,
+    }
+
+    impl Uploads {
+        fn new() -> Self {
+            let dir = TempDir::new();
+            std::fs::create_dir_all(&dir.0).unwrap();
+            Uploads {
+                runtime: Runtime::new().unwrap(),
+                dir,
+                storage: Arc::new(MemoryStorage::new()),
+                appending: Appending::default(),
+            }
+        }
+
+        fn run(&mut self, resp: ResponseFuture) -> Response<Body> {
+            self.runtime.block_on(resp).unwrap()
+        }
+
+        fn create(&mut self, length: u64) -> String {
+            let req = Request::post("/uploads")
+                .header(UPLOAD_LENGTH, length)
+                .body(Body::empty())
+                .unwrap();
+            let resp = self.run(create(req, &self.dir.0));
+            assert_eq!(resp.status(), StatusCode::CREATED);
+            let id = self.runtime.block_on(resp.into_body().concat2()).unwrap();
+            String::from_utf8(id.to_vec()).unwrap()
+        }
+
+        fn append(&mut self, id: &str, offset: u64, body: Body) -> Response<Body> {
+            let req = Request::patch(format!("/uploads/{}", id))
+                .header(UPLOAD_OFFSET, offset)
+                .body(body)
+                .unwrap();
+            let resp = append(req, id, &self.dir.0, &self.appending);
+            self.run(resp)
+        }
+
+        fn finalize(&mut self, id: &str) -> Response<Body> {
+            let resp = finalize(id, &self.dir.0, self.storage.clone(), &self.appending);
+            self.run(resp)
+        }
+
+        fn stored(&mut self, id: &str) -> Vec<u8> {
+            let chunks = self.runtime.block_on(self.storage.get(id)).unwrap();
+            self.runtime.block_on(chunks.concat2()).unwrap().to_vec()
+        }
+    }
+
+    fn offset(resp: &Response<Body>) -> u64 {
+        resp.headers()[UPLOAD_OFFSET]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap()
+    }
+
+    /// A chunked body, it has no `Content-Length` to check up front
+    fn chunked(chunks: &[&'static [u8]]) -> Body {
+        let chunks = chunks
+            .iter()
+            .map(|&chunk| Ok::<_, Error>(chunk))
+            .collect::<Vec<_>>();
+        Body::wrap_stream(futures::stream::iter_result(chunks))
+    }
+
+    #[test]
+    fn upload_in_pieces() {
+        let mut uploads = Uploads::new();
+        let id = uploads.create(11);
+
+        let resp = uploads.append(&id, 0, "hello ".into());
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        assert_eq!(offset(&resp), 6);
+        let resp = uploads.append(&id, 6, chunked(&[b"wor", b"ld"]));
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        assert_eq!(offset(&resp), 11);
+
+        let resp = uploads.finalize(&id);
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(uploads.stored(&id), b"hello world");
+        let resp = uploads.run(head(&id, &uploads.dir.0));
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn offset_mismatch_conflicts() {
+        let mut uploads = Uploads::new();
+        let id = uploads.create(11);
+        let resp = uploads.append(&id, 3, "lo world".into());
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        uploads.append(&id, 0, "hello".into());
+        let resp = uploads.append(&id, 0, "hello".into());
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+        let resp = uploads.run(head(&id, &uploads.dir.0));
+        assert_eq!(offset(&resp), 5);
+    }
+
+    #[test]
+    fn declared_length_is_enforced() {
+        let mut uploads = Uploads::new();
+        let id = uploads.create(5);
+
+        let resp = uploads.append(&id, 0, "hello world".into());
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let resp = uploads.append(&id, 0, chunked(&[b"hel", b"lo world"]));
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        // The chunk that fit is kept
+        let resp = uploads.run(head(&id, &uploads.dir.0));
+        assert_eq!(offset(&resp), 3);
+
+        let resp = uploads.finalize(&id);
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+        assert_eq!(offset(&resp), 3);
+        uploads.append(&id, 3, "lo".into());
+        let resp = uploads.finalize(&id);
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(uploads.stored(&id), b"hello");
+    }
+
+    #[test]
+    fn finalize_while_appending_is_locked() {
+        let mut uploads = Uploads::new();
+        let id = uploads.create(5);
+        uploads.append(&id, 0, "hello".into());
+
+        let guard = uploads.appending.lock(&id).unwrap();
+        let resp = uploads.finalize(&id);
+        assert_eq!(resp.status(), StatusCode::LOCKED);
+        let resp = uploads.append(&id, 5, Body::empty());
+        assert_eq!(resp.status(), StatusCode::LOCKED);
+
+        drop(guard);
+        let resp = uploads.finalize(&id);
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
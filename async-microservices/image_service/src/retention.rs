@@ -0,0 +1,175 @@
+use crate::storage::{Stat, Storage};
+use futures::{future, Future, Stream};
+use log::{error, info, warn};
+use std::io::Error;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::timer::Interval;
+
+/// Files older than `ttl` are removed first, then the oldest remaining ones until the total size
+/// fits within `quota`
+#[derive(Clone, Copy)]
+pub struct Policy {
+    pub ttl: Option<Duration>,
+    pub quota: Option<u64>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Reason {
+    Expired,
+    OverQuota,
+}
+
+pub fn task(
+    storage: Arc<dyn Storage>,
+    policy: Policy,
+    every: Duration,
+) -> impl Future<Item = (), Error = ()> {
+    Interval::new_interval(every)
+        .map_err(|err| error!("retention timer failed: {}", err))
+        .for_each(move |_| {
+            // A failed sweep is retried on the next tick instead of stopping the task
+            sweep(storage.clone(), policy).then(|result| {
+                if let Err(err) = result {
+                    warn!("retention sweep failed: {}", err);
+                }
+                Ok(())
+            })
+        })
+}
+
+fn sweep(storage: Arc<dyn Storage>, policy: Policy) -> impl Future<Item = (), Error = Error> {
+    let now = SystemTime::now();
+    storage.list().and_then(move |files| {
+        let removals = select(files, policy, now)
+            .into_iter()
+            .map(move |(stat, reason)| {
+                storage.delete(&stat.id).map(move |removed| {
+                    if removed {
+                        let reason = match reason {
+                            Reason::Expired => "expired",
+                            Reason::OverQuota => "over quota",
+                        };
+                        info!("removed {} ({} bytes): {}", stat.id, stat.size, reason);
+                    }
+                })
+            });
+        future::join_all(removals).map(drop)
+    })
+}
+
+fn select(mut files: Vec<Stat>, policy: Policy, now: SystemTime) -> Vec<(Stat, Reason)> {
+    files.sort_by_key(|stat| stat.modified);
+
+    let mut selected = Vec::new();
+    if let Some(ttl) = policy.ttl {
+        let expired = files
+            .iter()
+            .take_while(|stat| now.duration_since(stat.modified).unwrap_or_default() > ttl)
+            .count();
+        selected.extend(files.drain(..expired).map(|stat| (stat, Reason::Expired)));
+    }
+    if let Some(quota) = policy.quota {
+        let mut total: u64 = files.iter().map(|stat| stat.size).sum();
+        let over = files
+            .iter()
+            .take_while(|stat| {
+                let over = total > quota;
+                total -= stat.size;
+                over
+            })
+            .count();
+        selected.extend(files.drain(..over).map(|stat| (stat, Reason::OverQuota)));
+    }
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Metadata;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    /// TTL in minutes, quota and the files expected to be removed
+    type Case = (Option<u32>, Option<u64>, Vec<(&'static str, Reason)>);
+
+    /// Runs `select` over files given as `(id, age in minutes, size)`
+    fn selected(files: &[(&str, u32, u64)], policy: Policy) -> Vec<(String, Reason)> {
+        let now = SystemTime::now();
+        let files = files
+            .iter()
+            .map(|&(id, age, size)| Stat {
+                id: id.to_owned(),
+                size,
+                modified: now - MINUTE * age,
+                metadata: Metadata::default(),
+            })
+            .collect();
+        select(files, policy, now)
+            .into_iter()
+            .map(|(stat, reason)| (stat.id, reason))
+            .collect()
+    }
+
+    #[test]
+    fn select_by_policy() {
+        use self::Reason::{Expired, OverQuota};
+        let files = [("c", 5, 40), ("a", 30, 10), ("d", 1, 50), ("b", 20, 20)];
+        let table: Vec<Case> = vec![
+            (None, None, vec![]),
+            // TTL only, the age has to go past it
+            (Some(10), None, vec![("a", Expired), ("b", Expired)]),
+            (Some(20), None, vec![("a", Expired)]),
+            (Some(60), None, vec![]),
+            // Quota only, the oldest go first until the rest fits
+            (None, Some(120), vec![]),
+            (None, Some(100), vec![("a", OverQuota), ("b", OverQuota)]),
+            (None, Some(95), vec![("a", OverQuota), ("b", OverQuota)]),
+            (
+                None,
+                Some(50),
+                vec![("a", OverQuota), ("b", OverQuota), ("c", OverQuota)],
+            ),
+            (
+                None,
+                Some(0),
+                vec![
+                    ("a", OverQuota),
+                    ("b", OverQuota),
+                    ("c", OverQuota),
+                    ("d", OverQuota),
+                ],
+            ),
+            // Both, expired files count towards the quota before anything else is removed
+            (Some(25), Some(100), vec![("a", Expired), ("b", OverQuota)]),
+            (Some(10), Some(100), vec![("a", Expired), ("b", Expired)]),
+            (
+                Some(25),
+                Some(50),
+                vec![("a", Expired), ("b", OverQuota), ("c", OverQuota)],
+            ),
+        ];
+        for (ttl, quota, expected) in table {
+            let policy = Policy {
+                ttl: ttl.map(|minutes| MINUTE * minutes),
+                quota,
+            };
+            let expected: Vec<_> = expected
+                .into_iter()
+                .map(|(id, reason)| (id.to_owned(), reason))
+                .collect();
+            let actual = selected(&files, policy);
+            assert_eq!(actual, expected, "ttl {:?}, quota {:?}", ttl, quota);
+        }
+    }
+
+    #[test]
+    fn select_from_empty_storage() {
+        let policy = Policy {
+            ttl: Some(MINUTE),
+            quota: Some(0),
+        };
+        assert!(selected(&[], policy).is_empty());
+    }
+}
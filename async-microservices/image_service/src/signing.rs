@@ -0,0 +1,241 @@
+use crate::resumable::ResponseFuture;
+use crate::storage::Storage;
+use crate::{query_pairs, response_with_code};
+use futures::{future, Future};
+use hmac::{Hmac, Mac};
+use hyper::header::{HeaderMap, AUTHORIZATION, CONTENT_TYPE};
+use hyper::{Body, Response, StatusCode};
+use sha2::Sha256;
+use std::cmp::min;
+use std::io::ErrorKind;
+use std::net::IpAddr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DEFAULT_TTL: u64 = 60 * 60;
+const MAX_TTL: u64 = 7 * 24 * 60 * 60;
+
+/*
+    Download links are signed as HMAC-SHA256 over `{id}:{expires}:{ip}`, where `ip` is empty
+    for links that aren't bound to a client:
+
+    /download/{id}?expires={unix seconds}&ip={client ip}&signature={hex}
+*/
+pub struct Signer {
+    key: Vec<u8>,
+}
+
+#[derive(Serialize)]
+struct Link {
+    url: String,
+    expires: u64,
+}
+
+impl Signer {
+    pub fn new(key: &[u8]) -> Self {
+        Self { key: key.to_vec() }
+    }
+
+    fn mac(&self, id: &str, expires: u64, ip: Option<IpAddr>) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_varkey(&self.key).expect("HMAC accepts any key size");
+        let ip = ip.map(|ip| ip.to_string()).unwrap_or_default();
+        mac.input(format!("{}:{}:{}", id, expires, ip).as_bytes());
+        mac
+    }
+
+    pub fn sign(&self, id: &str, expires: u64, ip: Option<IpAddr>) -> String {
+        let code = self.mac(id, expires, ip).result().code();
+        code.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// Checks the query string of a download request made by `remote`
+    pub fn verify(&self, id: &str, query: Option<&str>, remote: IpAddr) -> bool {
+        let mut expires = None;
+        let mut ip = None;
+        let mut signature = None;
+        for (key, value) in query_pairs(query) {
+            match key {
+                "expires" => expires = value.parse::<u64>().ok(),
+                "ip" => ip = value.parse::<IpAddr>().ok(),
+                "signature" => signature = decode_hex(value),
+                _ => {}
+            }
+        }
+
+        let (expires, signature) = match (expires, signature) {
+            (Some(expires), Some(signature)) => (expires, signature),
+            _ => return false,
+        };
+        if expires <= unix_now() || (ip.is_some() && ip != Some(remote)) {
+            return false;
+        }
+        self.mac(id, expires, ip).verify(&signature).is_ok()
+    }
+}
+
+/// Mints a link for an existing file, accepts `ttl` (seconds) and `ip` in the query string
+pub fn mint(
+    id: &str,
+    query: Option<&str>,
+    signer: &Signer,
+    storage: &dyn Storage,
+) -> ResponseFuture {
+    let mut ttl = DEFAULT_TTL;
+    let mut ip = None;
+    for (key, value) in query_pairs(query) {
+        match key {
+            "ttl" => match value.parse() {
+                Ok(value) if value > 0 => ttl = min(value, MAX_TTL),
+                _ => return response_with_code(StatusCode::BAD_REQUEST),
+            },
+            "ip" => match value.parse::<IpAddr>() {
+                Ok(value) => ip = Some(value),
+                Err(_) => return response_with_code(StatusCode::BAD_REQUEST),
+            },
+            _ => {}
+        }
+    }
+
+    let expires = unix_now() + ttl;
+    let signature = signer.sign(id, expires, ip);
+    let mut url = format!("/download/{}?expires={}", id, expires);
+    if let Some(ip) = ip {
+        url.push_str(&format!("&ip={}", ip));
+    }
+    url.push_str(&format!("&signature={}", signature));
+
+    let body = storage
+        .stat(id)
+        .and_then(move |_| -> ResponseFuture {
+            let body = serde_json::to_vec(&Link { url, expires }).expect("link is serializable");
+            let resp = Response::builder()
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(body))
+                .unwrap();
+            Box::new(future::ok(resp))
+        })
+        .or_else(|err| match err.kind() {
+            ErrorKind::NotFound => response_with_code(StatusCode::NOT_FOUND),
+            _ => Box::new(future::err(err)),
+        });
+    Box::new(body)
+}
+
+/// Checks for `Authorization: Bearer {token}`, comparing in constant time
+pub fn is_bearer(headers: &HeaderMap, token: &str) -> bool {
+    let value = match headers.get(AUTHORIZATION).map(|value| value.to_str()) {
+        Some(Ok(value)) => value,
+        _ => return false,
+    };
+    // Header values that are valid strings are visible ASCII, so slicing can't split a char
+    if value.len() <= 7 || !value[..7].eq_ignore_ascii_case("bearer ") {
+        return false;
+    }
+    let given = &value[7..];
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+/// Odd lengths fail on the last pair being out of bounds
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{context, status, ID};
+    use hyper::Request;
+    use std::net::SocketAddr;
+    use tokio::runtime::Runtime;
+
+    const KEY: &[u8] = b"signing key";
+
+    fn link(signer: &Signer, expires: u64, ip: Option<IpAddr>) -> String {
+        let mut query = format!("expires={}", expires);
+        if let Some(ip) = ip {
+            query.push_str(&format!("&ip={}", ip));
+        }
+        format!("{}&signature={}", query, signer.sign(ID, expires, ip))
+    }
+
+    fn client() -> IpAddr {
+        "10.0.0.1".parse().unwrap()
+    }
+
+    #[test]
+    fn sign_then_verify() {
+        let signer = Signer::new(KEY);
+        let expires = unix_now() + 60;
+        let query = link(&signer, expires, None);
+        assert!(signer.verify(ID, Some(&query), client()));
+        let query = link(&signer, expires, Some(client()));
+        assert!(signer.verify(ID, Some(&query), client()));
+    }
+
+    #[test]
+    fn reject_bad_links() {
+        let signer = Signer::new(KEY);
+        let expires = unix_now() + 60;
+        let query = link(&signer, expires, None);
+
+        // Any change to what was signed breaks the signature
+        assert!(!signer.verify("0123456789abcdefghij", Some(&query), client()));
+        let moved = query.replace(&expires.to_string(), &(expires + 1).to_string());
+        assert!(!signer.verify(ID, Some(&moved), client()));
+        let last = query.len() - 1;
+        let flip = if query.ends_with('0') { "1" } else { "0" };
+        let tampered = format!("{}{}", &query[..last], flip);
+        assert!(!signer.verify(ID, Some(&tampered), client()));
+        assert!(!Signer::new(b"other key").verify(ID, Some(&query), client()));
+
+        let expired = link(&signer, unix_now() - 1, None);
+        assert!(!signer.verify(ID, Some(&expired), client()));
+        let elsewhere = link(&signer, expires, Some("10.0.0.2".parse().unwrap()));
+        assert!(!signer.verify(ID, Some(&elsewhere), client()));
+
+        assert!(!signer.verify(ID, None, client()));
+        assert!(!signer.verify(ID, Some(&format!("expires={}", expires)), client()));
+        let odd = format!("{}0", query);
+        assert!(!signer.verify(ID, Some(&odd), client()));
+    }
+
+    #[test]
+    fn download_needs_a_valid_link() {
+        let mut runtime = Runtime::new().unwrap();
+        let context = context(&mut runtime, Some(Signer::new(KEY)));
+        let signer = context.signer.as_ref().unwrap();
+        let remote = SocketAddr::new(client(), 1234);
+        let download = |query: &str| {
+            let uri = format!("/download/{}?{}", ID, query);
+            Request::get(uri).body(Body::empty()).unwrap()
+        };
+
+        let expires = unix_now() + 60;
+        let valid = link(signer, expires, Some(client()));
+        let req = download(&valid);
+        assert_eq!(status(&mut runtime, &context, req, remote), StatusCode::OK);
+
+        let tampered = valid.replace(&expires.to_string(), &(expires + 1).to_string());
+        let expired = link(signer, unix_now() - 1, Some(client()));
+        let elsewhere = link(signer, expires, Some("10.0.0.2".parse().unwrap()));
+        for query in &["", tampered.as_str(), expired.as_str(), elsewhere.as_str()] {
+            let req = download(query);
+            let forbidden = StatusCode::FORBIDDEN;
+            assert_eq!(status(&mut runtime, &context, req, remote), forbidden);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use super::{ByteStream, Metadata, Stat, Storage, StorageFuture};
+use crate::other;
+use futures::{future, Future, Stream};
+use hyper_staticfile::FileChunkStream;
+use std::fs::Metadata as FileMetadata;
+use std::io::{Error, ErrorKind};
+use std::path::PathBuf;
+use tokio::fs::File;
+
+/// Keeps every file as-is in a single directory, named by its id, with the metadata as json
+/// files in the `meta` subdirectory
+pub struct FileStorage {
+    dir: PathBuf,
+}
+
+impl FileStorage {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Result<Self, Error> {
+        let dir = dir.into();
+        std::fs::create_dir_all(dir.join("meta"))?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, id: &str) -> PathBuf {
+        self.dir.join(id)
+    }
+
+    fn meta_path(&self, id: &str) -> PathBuf {
+        self.dir.join("meta").join(format!("{}.json", id))
+    }
+}
+
+impl Storage for FileStorage {
+    fn put(&self, id: &str, data: ByteStream, metadata: Metadata) -> StorageFuture<u64> {
+        let meta = match serde_json::to_vec(&metadata) {
+            Ok(meta) => meta,
+            Err(err) => return Box::new(future::err(other(err))),
+        };
+        let meta_path = self.meta_path(id);
+
+        let path = self.path(id);
+
+        let create_file = File::create(path.clone());
+        let write = create_file
+            .and_then(|file| {
+                data.fold((file, 0), |(file, written), chunk| {
+                    let len = chunk.len() as u64;
+                    tokio::io::write_all(file, chunk).map(move |(file, _)| (file, written + len))
+                })
+            })
+            // Don't leave a truncated file behind when the stream fails part way
+            .or_else(|err| tokio::fs::remove_file(path).then(|_| Err(err)));
+        let write_meta = write
+            .and_then(move |(_, written)| tokio::fs::write(meta_path, meta).map(move |_| written));
+        Box::new(write_meta)
+    }
+
+    fn get(&self, id: &str) -> StorageFuture<ByteStream> {
+        let open_file = File::open(self.path(id));
+        let chunks = open_file.map(|file| Box::new(FileChunkStream::new(file)) as ByteStream);
+        Box::new(chunks)
+    }
+
+    fn delete(&self, id: &str) -> StorageFuture<bool> {
+        let meta_path = self.meta_path(id);
+        let remove = tokio::fs::remove_file(self.path(id))
+            .map(|_| true)
+            .or_else(ignore_not_found(false))
+            .and_then(move |removed| {
+                tokio::fs::remove_file(meta_path)
+                    .map(|_| ())
+                    .or_else(ignore_not_found(()))
+                    .map(move |_| removed)
+            });
+        Box::new(remove)
+    }
+
+    fn stat(&self, id: &str) -> StorageFuture<Stat> {
+        let stat = stat_file(self.path(id), self.meta_path(id), id.to_owned());
+        Box::new(stat)
+    }
+
+    fn list(&self) -> StorageFuture<Vec<Stat>> {
+        let meta_dir = self.dir.join("meta");
+        let list = tokio::fs::read_dir(self.dir.clone())
+            .flatten_stream()
+            .and_then(|entry| {
+                let id = entry.file_name().to_string_lossy().into_owned();
+                tokio::fs::metadata(entry.path()).map(|metadata| (entry, id, metadata))
+            })
+            // Skips the directories, such as the metadata and resumable upload sessions
+            .filter(|(_, _, metadata)| metadata.is_file())
+            .and_then(move |(entry, id, _)| {
+                let meta_path = meta_dir.join(format!("{}.json", id));
+                stat_file(entry.path(), meta_path, id)
+            })
+            .collect();
+        Box::new(list)
+    }
+}
+
+fn stat_file(
+    path: PathBuf,
+    meta_path: PathBuf,
+    id: String,
+) -> impl Future<Item = Stat, Error = Error> {
+    // Files stored before metadata was kept don't have any
+    let read_meta = tokio::fs::read(meta_path)
+        .and_then(|meta| serde_json::from_slice(&meta).map_err(other))
+        .or_else(ignore_not_found(Metadata::default()));
+
+    tokio::fs::metadata(path)
+        .join(read_meta)
+        .and_then(|(file, metadata)| to_stat(id, &file, metadata))
+}
+
+fn to_stat(id: String, file: &FileMetadata, metadata: Metadata) -> Result<Stat, Error> {
+    if !file.is_file() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!("{} is not a file", id),
+        ));
+    }
+    Ok(Stat {
+        id,
+        size: file.len(),
+        modified: file.modified()?,
+        metadata,
+    })
+}
+
+fn ignore_not_found<T>(value: T) -> impl FnOnce(Error) -> Result<T, Error> {
+    move |err| match err.kind() {
+        ErrorKind::NotFound => Ok(value),
+        _ => Err(err),
+    }
+}
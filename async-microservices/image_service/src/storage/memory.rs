@@ -0,0 +1,95 @@
+use super::{ByteStream, Metadata, Stat, Storage, StorageFuture};
+use futures::{future, stream, Future, Stream};
+use hyper::Chunk;
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+struct Entry {
+    data: Vec<u8>,
+    modified: SystemTime,
+    metadata: Metadata,
+}
+
+/// Keeps every file in a map, nothing survives a restart
+#[derive(Clone, Default)]
+pub struct MemoryStorage {
+    files: Arc<RwLock<HashMap<String, Entry>>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn put(&self, id: &str, data: ByteStream, metadata: Metadata) -> StorageFuture<u64> {
+        let id = id.to_owned();
+        let files = self.files.clone();
+        let collect = data.fold(Vec::new(), |mut data, chunk| {
+            data.extend_from_slice(&chunk);
+            future::ok::<_, Error>(data)
+        });
+        let insert = collect.map(move |data| {
+            let written = data.len() as u64;
+            let entry = Entry {
+                data,
+                modified: SystemTime::now(),
+                metadata,
+            };
+            files
+                .write()
+                .expect("storage lock poisoned")
+                .insert(id, entry);
+            written
+        });
+        Box::new(insert)
+    }
+
+    fn get(&self, id: &str) -> StorageFuture<ByteStream> {
+        let files = self.files.read().expect("storage lock poisoned");
+        let result = match files.get(id) {
+            Some(entry) => {
+                let chunk = Chunk::from(entry.data.clone());
+                Ok(Box::new(stream::once(Ok(chunk))) as ByteStream)
+            }
+            None => Err(not_found(id)),
+        };
+        Box::new(future::result(result))
+    }
+
+    fn delete(&self, id: &str) -> StorageFuture<bool> {
+        let mut files = self.files.write().expect("storage lock poisoned");
+        Box::new(future::ok(files.remove(id).is_some()))
+    }
+
+    fn stat(&self, id: &str) -> StorageFuture<Stat> {
+        let files = self.files.read().expect("storage lock poisoned");
+        let result = files
+            .get(id)
+            .map(|entry| to_stat(id, entry))
+            .ok_or_else(|| not_found(id));
+        Box::new(future::result(result))
+    }
+
+    fn list(&self) -> StorageFuture<Vec<Stat>> {
+        let files = self.files.read().expect("storage lock poisoned");
+        let list = files.iter().map(|(id, entry)| to_stat(id, entry)).collect();
+        Box::new(future::ok(list))
+    }
+}
+
+fn to_stat(id: &str, entry: &Entry) -> Stat {
+    Stat {
+        id: id.to_owned(),
+        size: entry.data.len() as u64,
+        modified: entry.modified,
+        metadata: entry.metadata.clone(),
+    }
+}
+
+fn not_found(id: &str) -> Error {
+    Error::new(ErrorKind::NotFound, format!("{} not found", id))
+}
@@ -0,0 +1,164 @@
+mod fs;
+mod memory;
+
+pub use self::fs::FileStorage;
+pub use self::memory::MemoryStorage;
+
+use futures::{Future, Stream};
+use hyper::Chunk;
+use std::io::Error;
+use std::time::SystemTime;
+
+pub type ByteStream = Box<dyn Stream<Item = Chunk, Error = Error> + Send>;
+pub type StorageFuture<T> = Box<dyn Future<Item = T, Error = Error> + Send>;
+
+/// Details supplied by the uploader and kept alongside the file
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Metadata {
+    pub filename: Option<String>,
+}
+
+/// Size and modification time of a stored file
+#[derive(Clone, Debug)]
+pub struct Stat {
+    pub id: String,
+    pub size: u64,
+    pub modified: SystemTime,
+    pub metadata: Metadata,
+}
+
+/*
+    Everything the handlers need from a storage backend.
+
+    Missing files are reported as `ErrorKind::NotFound` by `get` and `stat`, `delete` resolves
+    to `false` instead so it can be called on ids that may already be gone.
+*/
+pub trait Storage: Send + Sync {
+    /// Stores the stream under `id`, resolving to the number of bytes written
+    fn put(&self, id: &str, data: ByteStream, metadata: Metadata) -> StorageFuture<u64>;
+
+    fn get(&self, id: &str) -> StorageFuture<ByteStream>;
+
+    fn delete(&self, id: &str) -> StorageFuture<bool>;
+
+    fn stat(&self, id: &str) -> StorageFuture<Stat>;
+
+    fn list(&self) -> StorageFuture<Vec<Stat>>;
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use futures::stream;
+    use rand::distributions::Alphanumeric;
+    use rand::{thread_rng, Rng};
+    use std::env;
+    use std::io::ErrorKind;
+    use tokio::runtime::Runtime;
+
+    fn data(chunks: &[&'static [u8]]) -> ByteStream {
+        let chunks = chunks
+            .iter()
+            .map(|&chunk| Chunk::from(chunk))
+            .collect::<Vec<_>>();
+        Box::new(stream::iter_ok(chunks))
+    }
+
+    fn named(filename: &str) -> Metadata {
+        Metadata {
+            filename: Some(filename.to_owned()),
+        }
+    }
+
+    fn read(runtime: &mut Runtime, storage: &dyn Storage, id: &str) -> Result<Vec<u8>, Error> {
+        let chunks = runtime.block_on(storage.get(id))?;
+        Ok(runtime.block_on(chunks.concat2())?.to_vec())
+    }
+
+    fn list(runtime: &mut Runtime, storage: &dyn Storage) -> Vec<(String, u64)> {
+        let mut stats = runtime.block_on(storage.list()).unwrap();
+        stats.sort_by(|a, b| a.id.cmp(&b.id));
+        stats.into_iter().map(|stat| (stat.id, stat.size)).collect()
+    }
+
+    fn check(storage: &dyn Storage) {
+        let mut runtime = Runtime::new().unwrap();
+        assert!(list(&mut runtime, storage).is_empty());
+
+        let put = storage.put("first", data(&[b"hello ", b"world"]), named("hello.txt"));
+        assert_eq!(runtime.block_on(put).unwrap(), 11);
+        let put = storage.put("second", data(&[]), Metadata::default());
+        assert_eq!(runtime.block_on(put).unwrap(), 0);
+
+        assert_eq!(
+            read(&mut runtime, storage, "first").unwrap(),
+            b"hello world"
+        );
+        assert!(read(&mut runtime, storage, "second").unwrap().is_empty());
+        let stat = runtime.block_on(storage.stat("first")).unwrap();
+        assert_eq!(stat.size, 11);
+        assert_eq!(stat.metadata.filename.as_deref(), Some("hello.txt"));
+        let stat = runtime.block_on(storage.stat("second")).unwrap();
+        assert_eq!(stat.metadata.filename, None);
+        assert_eq!(
+            list(&mut runtime, storage),
+            vec![("first".to_owned(), 11), ("second".to_owned(), 0)]
+        );
+
+        // Storing under an existing id replaces the file
+        let put = storage.put("first", data(&[b"bye"]), Metadata::default());
+        assert_eq!(runtime.block_on(put).unwrap(), 3);
+        assert_eq!(read(&mut runtime, storage, "first").unwrap(), b"bye");
+
+        assert!(runtime.block_on(storage.delete("first")).unwrap());
+        assert!(!runtime.block_on(storage.delete("first")).unwrap());
+        assert_eq!(list(&mut runtime, storage), vec![("second".to_owned(), 0)]);
+    }
+
+    fn check_missing(storage: &dyn Storage) {
+        let mut runtime = Runtime::new().unwrap();
+        let err = read(&mut runtime, storage, "missing").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        let err = runtime.block_on(storage.stat("missing")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert!(!runtime.block_on(storage.delete("missing")).unwrap());
+    }
+
+    /// A fresh directory under the system temp dir, removed again when dropped
+    pub(crate) struct TempDir(pub(crate) std::path::PathBuf);
+
+    impl TempDir {
+        pub(crate) fn new() -> Self {
+            let name: String = thread_rng().sample_iter(&Alphanumeric).take(10).collect();
+            TempDir(env::temp_dir().join(format!("image_service-{}", name)))
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            std::fs::remove_dir_all(&self.0).ok();
+        }
+    }
+
+    #[test]
+    fn memory_put_get_list_delete() {
+        check(&MemoryStorage::new());
+    }
+
+    #[test]
+    fn memory_missing_ids() {
+        check_missing(&MemoryStorage::new());
+    }
+
+    #[test]
+    fn fs_put_get_list_delete() {
+        let dir = TempDir::new();
+        check(&FileStorage::new(&dir.0).unwrap());
+    }
+
+    #[test]
+    fn fs_missing_ids() {
+        let dir = TempDir::new();
+        check_missing(&FileStorage::new(&dir.0).unwrap());
+    }
+}
@@ -0,0 +1,203 @@
+use crate::error::{self, ApiError};
+use crate::rng::{ensure, RequestError, RngRequest, RngResponse};
+use rand::Rng;
+use schemars::JsonSchema;
+use serde_json::Value;
+
+/// Upper bound for `count`, anything bigger belongs on the streaming endpoint
+pub const MAX_COUNT: u64 = 10_000;
+pub const MAX_BATCH_SIZE: usize = 100;
+/// Upper bounds for a whole request, summed over the items of a batch
+pub const MAX_TOTAL_VALUES: u64 = 10_000;
+pub const MAX_TOTAL_BYTES: u64 = 16 * 1024 * 1024;
+
+/// A request with an optional `count`, which asks for that many values
+#[derive(JsonSchema)]
+pub struct Sampling {
+    #[schemars(flatten)]
+    pub request: RngRequest,
+    pub count: Option<u64>,
+}
+
+impl Sampling {
+    /// `count` is split off before the request itself is deserialized, so errors inside the
+    /// request keep their field path (serde loses it for flattened fields)
+    pub fn from_value(value: Value) -> Result<Self, ApiError> {
+        let mut object = match value {
+            Value::Object(object) => object,
+            _ => {
+                return Err(ApiError::new(
+                    "invalid_request",
+                    "expected a request object",
+                ))
+            }
+        };
+        let count = match object.remove("count") {
+            Some(count) => Some(
+                serde_json::from_value(count)
+                    .map_err(|err| ApiError::new("invalid_request", err).at("count"))?,
+            ),
+            None => None,
+        };
+        let request = error::from_value(Value::Object(object))?;
+        Ok(Sampling { request, count })
+    }
+
+    /// The values and bytes of shuffled data drawn, counts past `MAX_COUNT` are refused later
+    fn size(&self) -> (u64, u64) {
+        let values = self.count.unwrap_or(1).min(MAX_COUNT);
+        let bytes = match &self.request {
+            RngRequest::Shuffle { data } => data.len() as u64 * values,
+            _ => 0,
+        };
+        (values, bytes)
+    }
+}
+
+/*
+    The body of `POST /random` is either a single request or an array of them:
+
+    {"distribution": "uuid", "count": 3}
+    [{"distribution": "uuid"}, {"distribution": "bernoulli", "parameters": {"p": 2}}]
+
+    Batch items are answered one by one, so an invalid item only fails itself and its error is
+    reported in its place in the response. The whole request is refused with `413` when it asks
+    for more than `MAX_TOTAL_VALUES` values or `MAX_TOTAL_BYTES` bytes of shuffled data.
+*/
+#[derive(Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum Outcome {
+    One(RngResponse),
+    Many(Vec<RngResponse>),
+    Error { error: ApiError },
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum Reply {
+    Single(Outcome),
+    Batch(Vec<Outcome>),
+}
+
+/// Whether the reply to `body` holds a sequence: a batch, a `count` of values, a palette or a
+/// sample. XML replies can't, serde-xml-rs has no way to write sequences
+pub fn has_sequence(body: &Value) -> bool {
+    match body {
+        Value::Array(_) => true,
+        Value::Object(object) => {
+            let distribution = object.get("distribution").and_then(Value::as_str);
+            object.contains_key("count")
+                || distribution == Some("palette")
+                || distribution == Some("sample")
+        }
+        _ => false,
+    }
+}
+
+/// Answers the body with values drawn from `rng`, in the order the requests were given
+pub fn handle<R: Rng>(body: Value, rng: &mut R) -> Result<Reply, ApiError> {
+    match body {
+        Value::Array(items) => {
+            if items.len() > MAX_BATCH_SIZE {
+                return Err(RequestError::BatchTooLarge {
+                    max: MAX_BATCH_SIZE,
+                }
+                .into());
+            }
+            let samplings = items
+                .into_iter()
+                .map(Sampling::from_value)
+                .collect::<Vec<_>>();
+            check_size(
+                samplings
+                    .iter()
+                    .filter_map(|sampling| sampling.as_ref().ok()),
+            )?;
+            let outcomes = samplings
+                .into_iter()
+                .map(|sampling| {
+                    sampling
+                        .and_then(|sampling| Ok(run(&sampling, rng)?))
+                        .unwrap_or_else(|error| Outcome::Error { error })
+                })
+                .collect();
+            Ok(Reply::Batch(outcomes))
+        }
+        body => {
+            let sampling = Sampling::from_value(body)?;
+            check_size(Some(&sampling))?;
+            Ok(Reply::Single(run(&sampling, rng)?))
+        }
+    }
+}
+
+fn check_size<'a, I>(samplings: I) -> Result<(), RequestError>
+where
+    I: IntoIterator<Item = &'a Sampling>,
+{
+    let (values, bytes) = samplings.into_iter().map(Sampling::size).fold(
+        (0u64, 0u64),
+        |(values, bytes), (more_values, more_bytes)| {
+            (values + more_values, bytes.saturating_add(more_bytes))
+        },
+    );
+    if values > MAX_TOTAL_VALUES {
+        return Err(RequestError::TooLarge {
+            what: "values",
+            max: MAX_TOTAL_VALUES,
+        });
+    }
+    if bytes > MAX_TOTAL_BYTES {
+        return Err(RequestError::TooLarge {
+            what: "bytes of shuffled data",
+            max: MAX_TOTAL_BYTES,
+        });
+    }
+    Ok(())
+}
+
+fn run<R: Rng>(sampling: &Sampling, rng: &mut R) -> Result<Outcome, RequestError> {
+    sampling.request.validate()?;
+    match sampling.count {
+        None => Ok(Outcome::One(sampling.request.generate(rng))),
+        Some(count) => {
+            ensure(
+                count > 0 && count <= MAX_COUNT,
+                "count",
+                "must be between 1 and 10000",
+            )?;
+            let values = (0..count).map(|_| sampling.request.generate(rng)).collect();
+            Ok(Outcome::Many(values))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Format;
+    use rand::thread_rng;
+    use serde_json::json;
+
+    #[test]
+    fn xml_writes_replies_without_sequences() {
+        let bodies = vec![
+            json!({"distribution": "uniform", "parameters": {"start": 0, "end": 10}}),
+            json!({"distribution": "poisson", "parameters": {"lambda": 3}}),
+            json!({"distribution": "shuffle", "parameters": {"data": "AQID"}}),
+            json!({"distribution": "color", "parameters": {"from": "black", "to": "white"}}),
+            json!({"distribution": "choice", "parameters": {"items": [{"item": "a", "weight": 1}]}}),
+            json!({"distribution": "uuid"}),
+            json!({"distribution": "password", "parameters": {"length": 8}}),
+            json!({"distribution": "uuid", "count": 2}),
+            json!({"distribution": "palette", "parameters": {"from": "black", "to": "white", "size": 2}}),
+            json!({"distribution": "sample", "parameters": {"items": ["a", "b"], "k": 1}}),
+            json!([{"distribution": "uuid"}]),
+        ];
+        for body in bodies {
+            let reply = handle(body.clone(), &mut thread_rng()).unwrap();
+            let written = Format::Xml.serialize(&reply);
+            assert_eq!(written.is_err(), has_sequence(&body), "{}", body);
+        }
+    }
+}
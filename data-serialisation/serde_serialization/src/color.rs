@@ -1,4 +1,7 @@
 use rand::distributions::Uniform;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Metadata, Schema, SchemaObject};
+use schemars::JsonSchema;
 use serde::{
     de::{self, Visitor},
     Deserialize, Deserializer, Serialize, Serializer,
@@ -8,31 +11,75 @@ use std::fmt;
 use std::num::ParseIntError;
 use std::str::FromStr;
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Color {
     pub red: u8,
     pub green: u8,
     pub blue: u8,
+    /// 0xFF is fully opaque
+    pub alpha: u8,
 }
 
 pub const WHITE: Color = Color {
     red: 0xFF,
     green: 0xFF,
     blue: 0xFF,
+    alpha: 0xFF,
 };
 
 pub const BLACK: Color = Color {
     red: 0x00,
     green: 0x00,
     blue: 0x00,
+    alpha: 0xFF,
 };
 
+impl Color {
+    pub fn rgb(red: u8, green: u8, blue: u8) -> Self {
+        Color {
+            red,
+            green,
+            blue,
+            alpha: 0xFF,
+        }
+    }
+
+    fn from_u32(rgb: u32) -> Self {
+        Color::rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+    }
+
+    /// Linear interpolation, `t` goes from 0.0 (`self`) to 1.0 (`to`)
+    pub fn mix(&self, to: &Color, t: f64) -> Color {
+        let channel = |from: u8, to: u8| {
+            (f64::from(from) + (f64::from(to) - f64::from(from)) * t).round() as u8
+        };
+        Color {
+            red: channel(self.red, to.red),
+            green: channel(self.green, to.green),
+            blue: channel(self.blue, to.blue),
+            alpha: channel(self.alpha, to.alpha),
+        }
+    }
+}
+
+/*
+    Colors are written as `white`, `black` or `#rrggbb`, with `#rrggbbaa` for translucent ones.
+    Every form `FromStr` accepts maps onto one of these, so a color survives a round trip
+    through serialization unchanged.
+*/
 impl fmt::Display for Color {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             &WHITE => f.write_str("white"),
             &BLACK => f.write_str("black"),
-            color => write!(f, "#{:02X}{:02x}{:02x}", color.red, color.green, color.blue),
+            color if color.alpha == 0xFF => {
+                write!(f, "#{:02x}{:02x}{:02x}", color.red, color.green, color.blue)
+            }
+            color => write!(
+                f,
+                "#{:02x}{:02x}{:02x}{:02x}",
+                color.red, color.green, color.blue, color.alpha
+            ),
         }
     }
 }
@@ -49,20 +96,193 @@ impl Serialize for Color {
 impl FromStr for Color {
     type Err = ColorError;
 
+    /// Accepts hex (`#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa`), `rgb()`/`rgba()`,
+    /// `hsl()`/`hsla()` and the CSS named colors
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "white" => Ok(WHITE.to_owned()),
-            "black" => Ok(BLACK.to_owned()),
-            s if s.starts_with("#") && s.len() == 7 => {
-                let red = u8::from_str_radix(&s[1..3], 16)?;
-                let green = u8::from_str_radix(&s[3..5], 16)?;
-                let blue = u8::from_str_radix(&s[5..7], 16)?;
-                Ok(Color { red, green, blue })
-            }
-            other => Err(ColorError::InvalidValue {
-                value: other.to_owned(),
-            }),
+        let s = s.trim();
+        let lower = s.to_ascii_lowercase();
+        if let Some('#') = s.chars().next() {
+            return parse_hex(&s[1..]);
+        }
+        if let Some(color) = named(&lower) {
+            return Ok(color);
+        }
+        let open = lower.find('(').ok_or_else(|| invalid(s))?;
+        if !lower.ends_with(')') {
+            return Err(invalid(s));
+        }
+        let args: Vec<&str> = lower[open + 1..lower.len() - 1]
+            .split(',')
+            .map(str::trim)
+            .collect();
+        let color = match (&lower[..open], args.as_slice()) {
+            ("rgb", [r, g, b]) | ("rgba", [r, g, b]) => parse_rgb(r, g, b, "1"),
+            ("rgb", [r, g, b, a]) | ("rgba", [r, g, b, a]) => parse_rgb(r, g, b, a),
+            ("hsl", [h, s, l]) | ("hsla", [h, s, l]) => parse_hsl(h, s, l, "1"),
+            ("hsl", [h, s, l, a]) | ("hsla", [h, s, l, a]) => parse_hsl(h, s, l, a),
+            _ => None,
+        };
+        color.ok_or_else(|| invalid(s))
+    }
+}
+
+fn invalid(value: &str) -> ColorError {
+    ColorError::InvalidValue {
+        value: value.to_owned(),
+    }
+}
+
+fn parse_hex(digits: &str) -> Result<Color, ColorError> {
+    if !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return Err(invalid(&format!("#{}", digits)));
+    }
+    // Short forms repeat every digit, `#f80` is `#ff8800`
+    let expanded: String = match digits.len() {
+        3 | 4 => digits.chars().flat_map(|c| vec![c, c]).collect(),
+        6 | 8 => digits.to_owned(),
+        _ => return Err(invalid(&format!("#{}", digits))),
+    };
+    let channel = |i: usize| u8::from_str_radix(&expanded[i..i + 2], 16);
+    let alpha = if expanded.len() == 8 {
+        channel(6)?
+    } else {
+        0xFF
+    };
+    Ok(Color {
+        red: channel(0)?,
+        green: channel(2)?,
+        blue: channel(4)?,
+        alpha,
+    })
+}
+
+/// A number, or a percentage when it ends with `%`
+fn number(value: &str) -> Option<(f64, bool)> {
+    let (number, percent) = match value.rfind('%') {
+        Some(pos) if pos + 1 == value.len() => (&value[..pos], true),
+        _ => (value, false),
+    };
+    let number: f64 = number.trim().parse().ok()?;
+    if number.is_finite() {
+        Some((number, percent))
+    } else {
+        None
+    }
+}
+
+/// Out of range values are rejected rather than clamped
+fn to_channel(value: f64) -> Option<u8> {
+    if (0.0..=255.0).contains(&value) {
+        Some(value.round() as u8)
+    } else {
+        None
+    }
+}
+
+fn rgb_component(value: &str) -> Option<u8> {
+    match number(value)? {
+        (percent, true) => to_channel(percent / 100.0 * 255.0),
+        (value, false) => to_channel(value),
+    }
+}
+
+fn alpha(value: &str) -> Option<u8> {
+    match number(value)? {
+        (percent, true) => to_channel(percent / 100.0 * 255.0),
+        (fraction, false) => to_channel(fraction * 255.0),
+    }
+}
+
+fn percentage(value: &str) -> Option<f64> {
+    match number(value)? {
+        (percent, true) if (0.0..=100.0).contains(&percent) => Some(percent / 100.0),
+        _ => None,
+    }
+}
+
+fn parse_rgb(red: &str, green: &str, blue: &str, alpha_value: &str) -> Option<Color> {
+    Some(Color {
+        red: rgb_component(red)?,
+        green: rgb_component(green)?,
+        blue: rgb_component(blue)?,
+        alpha: alpha(alpha_value)?,
+    })
+}
+
+fn parse_hsl(hue: &str, saturation: &str, lightness: &str, alpha_value: &str) -> Option<Color> {
+    let hue = match number(hue.trim_end_matches("deg"))? {
+        (hue, false) => hue.rem_euclid(360.0) / 360.0,
+        (_, true) => return None,
+    };
+    let saturation = percentage(saturation)?;
+    let lightness = percentage(lightness)?;
+
+    // The conversion from the CSS Color Module
+    let m2 = if lightness <= 0.5 {
+        lightness * (saturation + 1.0)
+    } else {
+        lightness + saturation - lightness * saturation
+    };
+    let m1 = lightness * 2.0 - m2;
+    let hue_to_rgb = |h: f64| {
+        let h = h.rem_euclid(1.0);
+        let value = if h * 6.0 < 1.0 {
+            m1 + (m2 - m1) * h * 6.0
+        } else if h * 2.0 < 1.0 {
+            m2
+        } else if h * 3.0 < 2.0 {
+            m1 + (m2 - m1) * (2.0 / 3.0 - h) * 6.0
+        } else {
+            m1
+        };
+        (value * 255.0).round() as u8
+    };
+    Some(Color {
+        red: hue_to_rgb(hue + 1.0 / 3.0),
+        green: hue_to_rgb(hue),
+        blue: hue_to_rgb(hue - 1.0 / 3.0),
+        alpha: alpha(alpha_value)?,
+    })
+}
+
+fn named(name: &str) -> Option<Color> {
+    if name == "transparent" {
+        return Some(Color {
+            alpha: 0x00,
+            ..BLACK
+        });
+    }
+    NAMED_COLORS
+        .iter()
+        .find(|&&(named, _)| named == name)
+        .map(|&(_, rgb)| Color::from_u32(rgb))
+}
+
+impl JsonSchema for Color {
+    fn schema_name() -> String {
+        "Color".to_owned()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        let metadata = Metadata {
+            description: Some(
+                "A CSS color: `#rgb`, `#rrggbb`, `#rrggbbaa`, `rgb()`, `rgba()`, `hsl()`, \
+                 `hsla()` or a color name"
+                    .to_owned(),
+            ),
+            examples: vec![
+                "#ff8800".into(),
+                "rgba(0, 0, 255, 0.5)".into(),
+                "white".into(),
+            ],
+            ..Metadata::default()
+        };
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            metadata: Some(Box::new(metadata)),
+            ..SchemaObject::default()
         }
+        .into()
     }
 }
 
@@ -119,3 +339,254 @@ pub fn color_range(from: u8, to: u8) -> Uniform<u8> {
     let (from, to) = (min(from, to), max(from, to));
     Uniform::new_inclusive(from, to)
 }
+
+/// The CSS named colors
+static NAMED_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xF0F8FF),
+    ("antiquewhite", 0xFAEBD7),
+    ("aqua", 0x00FFFF),
+    ("aquamarine", 0x7FFFD4),
+    ("azure", 0xF0FFFF),
+    ("beige", 0xF5F5DC),
+    ("bisque", 0xFFE4C4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xFFEBCD),
+    ("blue", 0x0000FF),
+    ("blueviolet", 0x8A2BE2),
+    ("brown", 0xA52A2A),
+    ("burlywood", 0xDEB887),
+    ("cadetblue", 0x5F9EA0),
+    ("chartreuse", 0x7FFF00),
+    ("chocolate", 0xD2691E),
+    ("coral", 0xFF7F50),
+    ("cornflowerblue", 0x6495ED),
+    ("cornsilk", 0xFFF8DC),
+    ("crimson", 0xDC143C),
+    ("cyan", 0x00FFFF),
+    ("darkblue", 0x00008B),
+    ("darkcyan", 0x008B8B),
+    ("darkgoldenrod", 0xB8860B),
+    ("darkgray", 0xA9A9A9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xA9A9A9),
+    ("darkkhaki", 0xBDB76B),
+    ("darkmagenta", 0x8B008B),
+    ("darkolivegreen", 0x556B2F),
+    ("darkorange", 0xFF8C00),
+    ("darkorchid", 0x9932CC),
+    ("darkred", 0x8B0000),
+    ("darksalmon", 0xE9967A),
+    ("darkseagreen", 0x8FBC8F),
+    ("darkslateblue", 0x483D8B),
+    ("darkslategray", 0x2F4F4F),
+    ("darkslategrey", 0x2F4F4F),
+    ("darkturquoise", 0x00CED1),
+    ("darkviolet", 0x9400D3),
+    ("deeppink", 0xFF1493),
+    ("deepskyblue", 0x00BFFF),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1E90FF),
+    ("firebrick", 0xB22222),
+    ("floralwhite", 0xFFFAF0),
+    ("forestgreen", 0x228B22),
+    ("fuchsia", 0xFF00FF),
+    ("gainsboro", 0xDCDCDC),
+    ("ghostwhite", 0xF8F8FF),
+    ("gold", 0xFFD700),
+    ("goldenrod", 0xDAA520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xADFF2F),
+    ("grey", 0x808080),
+    ("honeydew", 0xF0FFF0),
+    ("hotpink", 0xFF69B4),
+    ("indianred", 0xCD5C5C),
+    ("indigo", 0x4B0082),
+    ("ivory", 0xFFFFF0),
+    ("khaki", 0xF0E68C),
+    ("lavender", 0xE6E6FA),
+    ("lavenderblush", 0xFFF0F5),
+    ("lawngreen", 0x7CFC00),
+    ("lemonchiffon", 0xFFFACD),
+    ("lightblue", 0xADD8E6),
+    ("lightcoral", 0xF08080),
+    ("lightcyan", 0xE0FFFF),
+    ("lightgoldenrodyellow", 0xFAFAD2),
+    ("lightgray", 0xD3D3D3),
+    ("lightgreen", 0x90EE90),
+    ("lightgrey", 0xD3D3D3),
+    ("lightpink", 0xFFB6C1),
+    ("lightsalmon", 0xFFA07A),
+    ("lightseagreen", 0x20B2AA),
+    ("lightskyblue", 0x87CEFA),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xB0C4DE),
+    ("lightyellow", 0xFFFFE0),
+    ("lime", 0x00FF00),
+    ("limegreen", 0x32CD32),
+    ("linen", 0xFAF0E6),
+    ("magenta", 0xFF00FF),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66CDAA),
+    ("mediumblue", 0x0000CD),
+    ("mediumorchid", 0xBA55D3),
+    ("mediumpurple", 0x9370DB),
+    ("mediumseagreen", 0x3CB371),
+    ("mediumslateblue", 0x7B68EE),
+    ("mediumspringgreen", 0x00FA9A),
+    ("mediumturquoise", 0x48D1CC),
+    ("mediumvioletred", 0xC71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xF5FFFA),
+    ("mistyrose", 0xFFE4E1),
+    ("moccasin", 0xFFE4B5),
+    ("navajowhite", 0xFFDEAD),
+    ("navy", 0x000080),
+    ("oldlace", 0xFDF5E6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6B8E23),
+    ("orange", 0xFFA500),
+    ("orangered", 0xFF4500),
+    ("orchid", 0xDA70D6),
+    ("palegoldenrod", 0xEEE8AA),
+    ("palegreen", 0x98FB98),
+    ("paleturquoise", 0xAFEEEE),
+    ("palevioletred", 0xDB7093),
+    ("papayawhip", 0xFFEFD5),
+    ("peachpuff", 0xFFDAB9),
+    ("peru", 0xCD853F),
+    ("pink", 0xFFC0CB),
+    ("plum", 0xDDA0DD),
+    ("powderblue", 0xB0E0E6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xFF0000),
+    ("rosybrown", 0xBC8F8F),
+    ("royalblue", 0x4169E1),
+    ("saddlebrown", 0x8B4513),
+    ("salmon", 0xFA8072),
+    ("sandybrown", 0xF4A460),
+    ("seagreen", 0x2E8B57),
+    ("seashell", 0xFFF5EE),
+    ("sienna", 0xA0522D),
+    ("silver", 0xC0C0C0),
+    ("skyblue", 0x87CEEB),
+    ("slateblue", 0x6A5ACD),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xFFFAFA),
+    ("springgreen", 0x00FF7F),
+    ("steelblue", 0x4682B4),
+    ("tan", 0xD2B48C),
+    ("teal", 0x008080),
+    ("thistle", 0xD8BFD8),
+    ("tomato", 0xFF6347),
+    ("turquoise", 0x40E0D0),
+    ("violet", 0xEE82EE),
+    ("wheat", 0xF5DEB3),
+    ("white", 0xFFFFFF),
+    ("whitesmoke", 0xF5F5F5),
+    ("yellow", 0xFFFF00),
+    ("yellowgreen", 0x9ACD32),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: &str) -> Color {
+        value.parse().unwrap()
+    }
+
+    fn rgba(red: u8, green: u8, blue: u8, alpha: u8) -> Color {
+        Color {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let colors = [
+            WHITE,
+            BLACK,
+            Color::rgb(0x12, 0xab, 0xef),
+            rgba(0x12, 0xab, 0xef, 0x80),
+            rgba(0, 0, 0, 0),
+            rgba(0xff, 0xff, 0xff, 0xfe),
+        ];
+        for color in colors.iter() {
+            assert_eq!(&parse(&color.to_string()), color);
+        }
+    }
+
+    #[test]
+    fn every_form_displays_canonically() {
+        let cases = [
+            ("#F80", "#ff8800"),
+            ("#f808", "#ff880088"),
+            (" #12abef ", "#12abef"),
+            ("#12abef80", "#12abef80"),
+            ("#ffffff", "white"),
+            ("#000000ff", "black"),
+            ("rgb(255, 136, 0)", "#ff8800"),
+            ("RGBA(100%, 0%, 0%, 0.5)", "#ff000080"),
+            ("rgb(0, 0, 255, 50%)", "#0000ff80"),
+            ("hsl(120, 100%, 25%)", "#008000"),
+            ("hsla(-120deg, 100%, 50%, 1)", "#0000ff"),
+            ("hsl(0, 0%, 100%)", "white"),
+            ("RebeccaPurple", "#663399"),
+            ("transparent", "#00000000"),
+        ];
+        for &(value, expected) in cases.iter() {
+            let color = parse(value);
+            assert_eq!(color.to_string(), expected, "{}", value);
+            assert_eq!(parse(expected), color, "{}", value);
+        }
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        let cases = [
+            "",
+            "#",
+            "#12",
+            "#12345",
+            "#1234567",
+            "#gg0000",
+            "#+12345",
+            "blurple",
+            "rgb(256, 0, 0)",
+            "rgb(-1, 0, 0)",
+            "rgb(0, 0)",
+            "rgb(0, 0, 0, 0, 0)",
+            "rgb(0, 0, 0",
+            "rgb(x, 0, 0)",
+            "rgba(0, 0, 0, 1.5)",
+            "hsl(10%, 50%, 50%)",
+            "hsl(10, 50, 50%)",
+            "hsl(10, 150%, 50%)",
+            "cmyk(0, 0, 0, 0)",
+        ];
+        for value in cases.iter() {
+            match value.parse::<Color>() {
+                Err(ColorError::InvalidValue { .. }) => {}
+                other => panic!("{:?} parsed as {:?}", value, other),
+            }
+        }
+    }
+
+    #[test]
+    fn serde_round_trips() {
+        let color = rgba(0x12, 0xab, 0xef, 0x80);
+        let json = serde_json::to_string(&color).unwrap();
+        assert_eq!(json, r##""#12abef80""##);
+        assert_eq!(serde_json::from_str::<Color>(&json).unwrap(), color);
+        assert!(serde_json::from_str::<Color>(r#""nope""#).is_err());
+        assert!(serde_json::from_str::<Color>("42").is_err());
+    }
+}
@@ -0,0 +1,96 @@
+use crate::rng::RequestError;
+use hyper::header::{ALLOW, CONTENT_TYPE};
+use hyper::{Body, Response, StatusCode};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fmt::Display;
+
+/*
+    Every error is answered with a JSON body, whatever format was negotiated:
+
+    {"code": "invalid_parameter", "message": "parameters.std_dev must be ...", "field": "parameters.std_dev"}
+
+    `code` is meant for programs and never changes for a given kind of error, `field` is only
+    present when a single field of the request is to blame.
+*/
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ApiError {
+    pub code: &'static str,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+}
+
+impl ApiError {
+    pub fn new<M: Display>(code: &'static str, message: M) -> Self {
+        ApiError {
+            code,
+            message: message.to_string(),
+            field: None,
+        }
+    }
+
+    pub fn at<F: Into<String>>(mut self, field: F) -> Self {
+        self.field = Some(field.into());
+        self
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self.code {
+            "malformed_body" => StatusCode::BAD_REQUEST,
+            "not_found" | "session_not_found" => StatusCode::NOT_FOUND,
+            "method_not_allowed" => StatusCode::METHOD_NOT_ALLOWED,
+            "not_acceptable" => StatusCode::NOT_ACCEPTABLE,
+            "unsupported_media_type" => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "request_too_large" => StatusCode::PAYLOAD_TOO_LARGE,
+            "too_many_sessions" => StatusCode::SERVICE_UNAVAILABLE,
+            "internal_error" => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    pub fn into_response(self) -> Response<Body> {
+        let body = serde_json::to_vec(&self).expect("errors are serializable");
+        Response::builder()
+            .status(self.status())
+            .header(CONTENT_TYPE, "application/json")
+            .body(body.into())
+            .unwrap()
+    }
+}
+
+impl From<RequestError> for ApiError {
+    fn from(err: RequestError) -> Self {
+        match err {
+            RequestError::InvalidParameter { field, .. } => {
+                ApiError::new("invalid_parameter", &err).at(field)
+            }
+            RequestError::BatchTooLarge { .. } => ApiError::new("batch_too_large", &err),
+            RequestError::TooLarge { .. } => ApiError::new("request_too_large", &err),
+        }
+    }
+}
+
+/// Deserializes `value`, keeping the path of the field that failed, `parameters.std_dev` etc.
+pub fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, ApiError> {
+    serde_path_to_error::deserialize(value).map_err(|err| {
+        let field = err.path().to_string();
+        let error = ApiError::new("invalid_request", err.into_inner());
+        if field == "." {
+            error
+        } else {
+            error.at(field)
+        }
+    })
+}
+
+pub fn not_found() -> Response<Body> {
+    ApiError::new("not_found", "no such resource").into_response()
+}
+
+pub fn method_not_allowed(allow: &'static str) -> Response<Body> {
+    let mut resp = ApiError::new("method_not_allowed", "method not allowed").into_response();
+    resp.headers_mut().insert(ALLOW, allow.parse().unwrap());
+    resp
+}
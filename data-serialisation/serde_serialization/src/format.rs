@@ -0,0 +1,136 @@
+use failure::Error;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::cmp::Ordering;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Cbor,
+    MessagePack,
+    Yaml,
+    Bincode,
+    Xml,
+}
+
+impl Format {
+    /// The short names accepted by the `?format=` query parameter
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(Format::Json),
+            "cbor" => Some(Format::Cbor),
+            "msgpack" | "messagepack" => Some(Format::MessagePack),
+            "yaml" => Some(Format::Yaml),
+            "bincode" => Some(Format::Bincode),
+            "xml" => Some(Format::Xml),
+            _ => None,
+        }
+    }
+
+    pub fn from_media_type(media_type: &str) -> Option<Self> {
+        match media_type.to_ascii_lowercase().as_str() {
+            "application/json" | "application/*" | "*/*" => Some(Format::Json),
+            "application/cbor" => Some(Format::Cbor),
+            "application/msgpack" | "application/x-msgpack" => Some(Format::MessagePack),
+            "application/yaml" | "application/x-yaml" | "text/yaml" => Some(Format::Yaml),
+            "application/bincode" | "application/x-bincode" => Some(Format::Bincode),
+            "application/xml" | "text/xml" => Some(Format::Xml),
+            _ => None,
+        }
+    }
+
+    /// Request bodies need a self-describing format to carry the tagged `RngRequest`
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let media_type = content_type.split(';').next()?.trim();
+        if media_type.contains('*') {
+            return None;
+        }
+        match Self::from_media_type(media_type)? {
+            format @ Format::Json | format @ Format::Cbor | format @ Format::MessagePack => {
+                Some(format)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn media_type(self) -> &'static str {
+        match self {
+            Format::Json => "application/json",
+            Format::Cbor => "application/cbor",
+            Format::MessagePack => "application/msgpack",
+            Format::Yaml => "application/yaml",
+            Format::Bincode => "application/bincode",
+            Format::Xml => "application/xml",
+        }
+    }
+
+    pub fn serialize<T: Serialize>(self, value: &T) -> Result<Vec<u8>, Error> {
+        match self {
+            Format::Json => Ok(serde_json::to_vec(value)?),
+            Format::Cbor => Ok(serde_cbor::to_vec(value)?),
+            Format::MessagePack => Ok(rmp_serde::to_vec(value)?),
+            Format::Yaml => Ok(serde_yaml::to_vec(value)?),
+            Format::Bincode => Ok(bincode::serialize(value)?),
+            Format::Xml => serde_xml_rs::to_string(value)
+                .map(String::into_bytes)
+                .map_err(|err| format_err!("{}", err)),
+        }
+    }
+
+    pub fn deserialize<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T, Error> {
+        match self {
+            Format::Json => Ok(serde_json::from_slice(bytes)?),
+            Format::Cbor => Ok(serde_cbor::from_slice(bytes)?),
+            Format::MessagePack => Ok(rmp_serde::from_slice(bytes)?),
+            format => Err(format_err!("{:?} request bodies aren't supported", format)),
+        }
+    }
+}
+
+/*
+    The `?format=` query parameter takes precedence over the `Accept` header, a request with
+    neither gets JSON. `None` means nothing acceptable is supported and should be answered with
+    406 Not Acceptable.
+*/
+pub fn negotiate(query_format: Option<&str>, accept: Option<&str>) -> Option<Format> {
+    if let Some(name) = query_format {
+        return Format::from_name(name);
+    }
+    match accept {
+        Some(accept) if !accept.trim().is_empty() => accepted_media_types(accept)
+            .into_iter()
+            .filter_map(Format::from_media_type)
+            .next(),
+        _ => Some(Format::Json),
+    }
+}
+
+/// The media types of an `Accept` header, most preferred first, dropping the ones with `q=0`
+pub fn accepted_media_types(accept: &str) -> Vec<&str> {
+    let mut ranges: Vec<(&str, f32)> = accept
+        .split(',')
+        .filter_map(|range| {
+            let mut params = range.split(';').map(str::trim);
+            let media_type = params.next()?;
+            let quality = params
+                .filter_map(|param| {
+                    let mut parts = param.splitn(2, '=');
+                    match (parts.next(), parts.next()) {
+                        (Some("q"), Some(value)) => value.trim().parse().ok(),
+                        _ => None,
+                    }
+                })
+                .next()
+                .unwrap_or(1.0);
+            Some((media_type, quality))
+        })
+        .filter(|&(_, quality)| quality > 0.0)
+        .collect();
+    // A stable sort keeps the client's order for equal qualities
+    ranges.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+
+    ranges
+        .into_iter()
+        .map(|(media_type, _)| media_type)
+        .collect()
+}
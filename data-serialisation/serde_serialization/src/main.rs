@@ -7,139 +7,340 @@ extern crate serde;
 #[macro_use]
 extern crate serde_derive;
 extern crate base64;
+extern crate schemars;
 extern crate serde_json;
+extern crate serde_path_to_error;
 #[macro_use]
 extern crate base64_serde;
+extern crate bincode;
 extern crate queryst;
+extern crate rmp_serde;
 extern crate serde_cbor;
+extern crate serde_xml_rs;
+extern crate serde_yaml;
+extern crate tokio_threadpool;
 
+mod batch;
 mod color;
+mod error;
+mod format;
+mod rng;
+mod schema;
+mod selftest;
+mod sessions;
+mod stats;
+mod stream;
 
-use base64::STANDARD;
-use color::{color_range, Color};
-use failure::Error;
+use batch::Sampling;
+use error::ApiError;
+use format::Format;
 use futures::{future, Future, Stream};
+use hyper::header::{HeaderMap, ACCEPT, CONTENT_TYPE};
 use hyper::service::service_fn;
 use hyper::{Body, Method, Request, Response, Server, StatusCode};
-use rand::distributions::{Bernoulli, Normal, Uniform};
-use rand::Rng;
 use serde_json::Value;
-use std::ops::Range;
-
-base64_serde_type!(Base64Standard, STANDARD);
-
-#[derive(Deserialize)]
-#[serde(tag = "distribution", content = "parameters", rename_all = "lowercase")]
-enum RngRequest {
-    Uniform {
-        #[serde(flatten)]
-        range: Range<i32>,
-    },
-    Normal {
-        mean: f64,
-        std_dev: f64,
-    },
-    Bernoulli {
-        p: f64,
-    },
-    Shuffle {
-        #[serde(with = "Base64Standard")]
-        data: Vec<u8>,
-    },
-    Color {
-        from: Color,
-        to: Color,
-    },
-}
-
-#[derive(Serialize)]
-#[serde(rename_all = "lowercase")]
-enum RngResponse {
-    Value(f64),
-    #[serde(with = "Base64Standard")]
-    Bytes(Vec<u8>),
-    Color(Color),
-}
+use sessions::{SessionRequest, Sessions};
+use std::sync::Arc;
+use stream::Samples;
 
 static INDEX: &[u8] = b"Random Microservice";
 
 fn main() {
     let addr = ([127, 0, 0, 1], 8080).into();
     let builder = Server::bind(&addr);
-    let server = builder.serve(|| service_fn(microservice_handler));
+    let sessions = Arc::new(Sessions::default());
+    let server = builder.serve(move || {
+        let sessions = sessions.clone();
+        service_fn(move |req| microservice_handler(req, &sessions))
+    });
     let server = server.map_err(drop);
     hyper::rt::run(server);
 }
 
 fn microservice_handler(
     req: Request<Body>,
+    sessions: &Arc<Sessions>,
 ) -> Box<dyn Future<Item = Response<Body>, Error = hyper::Error> + Send> {
     match (req.method(), req.uri().path()) {
         (&Method::GET, "/") | (&Method::GET, "/random") => {
             Box::new(future::ok(Response::new(INDEX.into())))
         }
         (&Method::POST, "/random") => {
+            let uri = req.uri().query().unwrap_or("");
+            let query = queryst::parse(uri).unwrap_or(Value::Null);
+            let format = {
+                let accept = req
+                    .headers()
+                    .get(ACCEPT)
+                    .and_then(|value| value.to_str().ok());
+                format::negotiate(query["format"].as_str(), accept)
+            };
+            let format = match format {
+                Some(format) => format,
+                None => return error_response(not_acceptable()),
+            };
+            let request_format = match request_format(req.headers()) {
+                Some(request_format) => request_format,
+                None => return error_response(unsupported_media_type()),
+            };
+            let session = query["session"].as_str().map(str::to_owned);
+            let sessions = sessions.clone();
+            let body = req.into_body().concat2().map(move |chunks| {
+                let res = read_body(request_format, chunks.as_ref())
+                    // Refused before drawing, so a session's values aren't used up on a reply
+                    // that can't be written
+                    .and_then(|body| {
+                        if format == Format::Xml && batch::has_sequence(&body) {
+                            Err(ApiError::new(
+                                "not_acceptable",
+                                "XML can't hold batches, counts, palettes or samples",
+                            ))
+                        } else {
+                            Ok(body)
+                        }
+                    })
+                    .and_then(|body| match session {
+                        Some(id) => sessions.draw(&id, |rng| batch::handle(body, rng)),
+                        None => batch::handle(body, &mut rand::thread_rng()),
+                    })
+                    // The format was negotiated already, failing to write it is a bug on our side
+                    .and_then(|resp| {
+                        format
+                            .serialize(&resp)
+                            .map_err(|err| ApiError::new("internal_error", err))
+                    });
+
+                match res {
+                    Ok(body) => Response::builder()
+                        .header(CONTENT_TYPE, format.media_type())
+                        .body(body.into())
+                        .unwrap(),
+                    Err(err) => err.into_response(),
+                }
+            });
+            Box::new(body)
+        }
+        (&Method::POST, "/random/stream") => {
             let format = {
                 let uri = req.uri().query().unwrap_or("");
                 let query = queryst::parse(uri).unwrap_or(Value::Null);
-                query["format"].as_str().unwrap_or("json").to_string()
+                let accept = req
+                    .headers()
+                    .get(ACCEPT)
+                    .and_then(|value| value.to_str().ok());
+                stream::negotiate(query["format"].as_str(), accept)
             };
-            let body = req.into_body().concat2().map(|chunks| {
-                let res = serde_json::from_slice::<RngRequest>(chunks.as_ref())
-                    .map(handle_request)
-                    .map_err(Error::from)
-                    .and_then(move |resp| serialize(&format, &resp));
+            let format = match format {
+                Some(format) => format,
+                None => return error_response(not_acceptable()),
+            };
+            let request_format = match request_format(req.headers()) {
+                Some(request_format) => request_format,
+                None => return error_response(unsupported_media_type()),
+            };
+            let body = req.into_body().concat2().map(move |chunks| {
+                let res = read_body(request_format, chunks.as_ref())
+                    .and_then(Sampling::from_value)
+                    .and_then(|sampling| Samples::new(sampling, format));
 
                 match res {
-                    Ok(body) => Response::new(body.into()),
-                    Err(err) => Response::builder()
-                        .status(StatusCode::UNPROCESSABLE_ENTITY)
-                        .body(err.to_string().into())
+                    Ok(samples) => Response::builder()
+                        .header(CONTENT_TYPE, format.media_type())
+                        .body(Body::wrap_stream(samples))
                         .unwrap(),
+                    Err(err) => err.into_response(),
                 }
             });
             Box::new(body)
         }
-        _ => unimplemented!("Methods have not been implemented yet"),
-    }
-}
-
-fn handle_request(request: RngRequest) -> RngResponse {
-    let mut rng = rand::thread_rng();
-    match request {
-        RngRequest::Uniform { range } => {
-            let value = rng.sample(Uniform::from(range)) as f64;
-            RngResponse::Value(value)
+        (&Method::GET, "/schema") => {
+            let body = serde_json::to_vec(&schema::schemas()).expect("schemas are serializable");
+            let resp = Response::builder()
+                .header(CONTENT_TYPE, "application/schema+json")
+                .body(body.into())
+                .unwrap();
+            Box::new(future::ok(resp))
         }
-        RngRequest::Normal { mean, std_dev } => {
-            let value = rng.sample(Normal::new(mean, std_dev)) as f64;
-            RngResponse::Value(value)
+        (&Method::POST, "/selftest") => {
+            let request_format = match request_format(req.headers()) {
+                Some(request_format) => request_format,
+                None => return error_response(unsupported_media_type()),
+            };
+            let body = req.into_body().concat2().and_then(move |chunks| {
+                let mut sampling =
+                    Some(read_body(request_format, chunks.as_ref()).and_then(Sampling::from_value));
+                // Drawing and sorting up to a million values would stall every connection
+                // served by the reactor, so the test runs where blocking is allowed
+                let report = future::poll_fn(move || {
+                    tokio_threadpool::blocking(|| {
+                        let sampling = sampling.take().expect("the test runs once");
+                        sampling
+                            .and_then(|sampling| selftest::run(sampling, &mut rand::thread_rng()))
+                    })
+                });
+                report.then(|res| {
+                    let res = res
+                        .unwrap_or_else(|err| Err(ApiError::new("internal_error", err)))
+                        .map(|report| {
+                            serde_json::to_vec(&report).expect("reports are serializable")
+                        });
+
+                    Ok(match res {
+                        Ok(body) => Response::builder()
+                            .header(CONTENT_TYPE, "application/json")
+                            .body(body.into())
+                            .unwrap(),
+                        Err(err) => err.into_response(),
+                    })
+                })
+            });
+            Box::new(body)
         }
-        RngRequest::Bernoulli { p } => {
-            let value = rng.sample(Bernoulli::new(p)) as i8 as f64;
-            RngResponse::Value(value)
+        (&Method::POST, "/sessions") => {
+            let request_format = match request_format(req.headers()) {
+                Some(request_format) => request_format,
+                None => return error_response(unsupported_media_type()),
+            };
+            let sessions = sessions.clone();
+            let body = req.into_body().concat2().map(move |chunks| {
+                let request = if chunks.is_empty() {
+                    Ok(SessionRequest::default())
+                } else {
+                    read_body(request_format, chunks.as_ref()).and_then(error::from_value)
+                };
+                let res = request
+                    .and_then(|request| sessions.create(request))
+                    .map(|session| {
+                        serde_json::to_vec(&session).expect("sessions are serializable")
+                    });
+
+                match res {
+                    Ok(body) => Response::builder()
+                        .status(StatusCode::CREATED)
+                        .header(CONTENT_TYPE, "application/json")
+                        .body(body.into())
+                        .unwrap(),
+                    Err(err) => err.into_response(),
+                }
+            });
+            Box::new(body)
         }
-        RngRequest::Shuffle { mut data } => {
-            rng.shuffle(&mut data);
-            RngResponse::Bytes(data)
+        (&Method::DELETE, path) if path.starts_with("/sessions/") => {
+            let id = &path["/sessions/".len()..];
+            if sessions.remove(id) {
+                let resp = Response::builder()
+                    .status(StatusCode::NO_CONTENT)
+                    .body(Body::empty())
+                    .unwrap();
+                Box::new(future::ok(resp))
+            } else {
+                error_response(ApiError::new("session_not_found", "no such session"))
+            }
         }
-        RngRequest::Color { from, to } => {
-            let red = rng.sample(color_range(from.red, to.red));
-            let green = rng.sample(color_range(from.green, to.green));
-            let blue = rng.sample(color_range(from.blue, to.blue));
-            RngResponse::Color(Color { red, green, blue })
+        (_, "/schema") => Box::new(future::ok(error::method_not_allowed("GET"))),
+        (_, "/selftest") => Box::new(future::ok(error::method_not_allowed("POST"))),
+        (_, "/sessions") => Box::new(future::ok(error::method_not_allowed("POST"))),
+        (_, path) if path.starts_with("/sessions/") => {
+            Box::new(future::ok(error::method_not_allowed("DELETE")))
         }
+        (_, "/") => Box::new(future::ok(error::method_not_allowed("GET"))),
+        (_, "/random") => Box::new(future::ok(error::method_not_allowed("GET, POST"))),
+        (_, "/random/stream") => Box::new(future::ok(error::method_not_allowed("POST"))),
+        _ => Box::new(future::ok(error::not_found())),
     }
 }
 
-fn serialize(format: &str, resp: &RngResponse) -> Result<Vec<u8>, Error> {
-    match format {
-        "json" => Ok(serde_json::to_vec(resp)?),
-        "cbor" => Ok(serde_cbor::to_vec(resp)?),
-        _ => Err(format_err!("unsupported format {}", format)),
+/// The format of a request body, taken from `Content-Type` and defaulting to JSON
+fn request_format(headers: &HeaderMap) -> Option<Format> {
+    match headers.get(CONTENT_TYPE) {
+        Some(value) => value.to_str().ok().and_then(Format::from_content_type),
+        None => Some(Format::Json),
     }
 }
 
-curl --header "Content-Type: application/json" --request POST \
---data '{"distribution": "uniform", "parameters": {"start": -100, "end": 100}}' \
-"http://localhost:8080/random?format=xml"
\ No newline at end of file
+/// Requests are read into a generic value first, so a body that can't be parsed at all is told
+/// apart from one with the wrong shape
+fn read_body(format: Format, body: &[u8]) -> Result<Value, ApiError> {
+    format
+        .deserialize(body)
+        .map_err(|err| ApiError::new("malformed_body", err))
+}
+
+fn not_acceptable() -> ApiError {
+    ApiError::new(
+        "not_acceptable",
+        "none of the accepted media types can be produced",
+    )
+}
+
+fn unsupported_media_type() -> ApiError {
+    ApiError::new(
+        "unsupported_media_type",
+        "request bodies must be JSON, CBOR or MessagePack",
+    )
+}
+
+fn error_response(
+    error: ApiError,
+) -> Box<dyn Future<Item = Response<Body>, Error = hyper::Error> + Send> {
+    Box::new(future::ok(error.into_response()))
+}
+
+/*
+    Example request, the response format can also be picked with `Accept: application/yaml` etc.
+
+    curl --header "Content-Type: application/json" --request POST \
+    --data '{"distribution": "uniform", "parameters": {"start": -100, "end": 100}}' \
+    "http://localhost:8080/random?format=xml"
+
+    Reproducible values come from a session, the response holds its id and seed:
+
+    curl --request POST --data '{"seed": 42}' \
+    --header "Content-Type: application/json" "http://localhost:8080/sessions"
+    curl --header "Content-Type: application/json" --request POST \
+    --data '{"distribution": "uuid"}' "http://localhost:8080/random?session={id}"
+
+    Streaming a million samples as NDJSON, `?format=cbor-seq` switches to a CBOR sequence:
+
+    curl --header "Content-Type: application/json" --request POST \
+    --data '{"distribution": "normal", "parameters": {"mean": 0, "std_dev": 1}, "count": 1000000}' \
+    "http://localhost:8080/random/stream"
+
+    JSON Schemas of the request and response bodies:
+
+    curl "http://localhost:8080/schema"
+*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random(query: &str, body: &str) -> (StatusCode, String) {
+        let req = Request::post(format!("/random?{}", query))
+            .header(CONTENT_TYPE, "application/json")
+            .body(body.to_owned().into())
+            .unwrap();
+        let sessions = Arc::new(Sessions::default());
+        let resp = microservice_handler(req, &sessions).wait().unwrap();
+        let status = resp.status();
+        let body = resp.into_body().concat2().wait().unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[test]
+    fn xml_replies() {
+        let (status, body) = random("format=xml", r#"{"distribution": "uuid"}"#);
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("<uuid>"), "{}", body);
+
+        let batch = r#"[{"distribution": "uuid"}, {"distribution": "uuid"}]"#;
+        let (status, body) = random("format=xml", batch);
+        assert_eq!(status, StatusCode::NOT_ACCEPTABLE);
+        assert!(body.contains("not_acceptable"), "{}", body);
+        let (status, _) = random("format=xml", r#"{"distribution": "uuid", "count": 2}"#);
+        assert_eq!(status, StatusCode::NOT_ACCEPTABLE);
+
+        let (status, _) = random("format=json", batch);
+        assert_eq!(status, StatusCode::OK);
+    }
+}
@@ -0,0 +1,478 @@
+use crate::color::{color_range, Color};
+use base64::STANDARD;
+use rand::distributions::{Bernoulli, Binomial, Exp, Gamma, LogNormal, Normal, Poisson, Uniform};
+use rand::{seq, Rng};
+use schemars::JsonSchema;
+use std::ops::Range;
+
+base64_serde_type!(Base64Standard, STANDARD);
+
+/// Upper bound for generated passwords, so a single request can't ask for megabytes
+const MAX_PASSWORD_LENGTH: usize = 1024;
+const MAX_PALETTE_SIZE: usize = 256;
+/// Past these the `rand` samplers lose precision and their rejection loops may never finish
+const MAX_LAMBDA: f64 = 1e6;
+const MAX_BINOMIAL_N: u64 = 1_000_000;
+
+const LOWERCASE: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &[u8] = b"0123456789";
+const SYMBOLS: &[u8] = b"!#$%&()*+-./:;<=>?@[]^_{|}~";
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(tag = "distribution", content = "parameters", rename_all = "lowercase")]
+pub enum RngRequest {
+    Uniform {
+        #[serde(flatten)]
+        range: Range<i32>,
+    },
+    Normal {
+        mean: f64,
+        std_dev: f64,
+    },
+    Bernoulli {
+        p: f64,
+    },
+    Exponential {
+        lambda: f64,
+    },
+    Poisson {
+        lambda: f64,
+    },
+    Gamma {
+        shape: f64,
+        scale: f64,
+    },
+    Beta {
+        alpha: f64,
+        beta: f64,
+    },
+    Binomial {
+        n: u64,
+        p: f64,
+    },
+    LogNormal {
+        mean: f64,
+        std_dev: f64,
+    },
+    Shuffle {
+        #[serde(with = "Base64Standard")]
+        #[schemars(with = "String")]
+        data: Vec<u8>,
+    },
+    Color {
+        from: Color,
+        to: Color,
+    },
+    /// `size` random colors between `from` and `to`, or evenly spaced ones for a gradient
+    Palette {
+        from: Color,
+        to: Color,
+        size: usize,
+        #[serde(default)]
+        gradient: bool,
+    },
+    Choice {
+        items: Vec<WeightedItem>,
+    },
+    Sample {
+        items: Vec<String>,
+        k: usize,
+    },
+    Uuid,
+    Password {
+        length: usize,
+        #[serde(flatten)]
+        rules: PasswordRules,
+    },
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct WeightedItem {
+    pub item: String,
+    pub weight: f64,
+}
+
+/// Character classes a password is drawn from, every enabled class appears at least once
+#[derive(Deserialize, JsonSchema)]
+pub struct PasswordRules {
+    #[serde(default = "enabled")]
+    pub lowercase: bool,
+    #[serde(default = "enabled")]
+    pub uppercase: bool,
+    #[serde(default = "enabled")]
+    pub digits: bool,
+    #[serde(default)]
+    pub symbols: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl PasswordRules {
+    fn classes(&self) -> Vec<&'static [u8]> {
+        let classes = [
+            (self.lowercase, LOWERCASE),
+            (self.uppercase, UPPERCASE),
+            (self.digits, DIGITS),
+            (self.symbols, SYMBOLS),
+        ];
+        classes
+            .iter()
+            .filter(|(enabled, _)| *enabled)
+            .map(|&(_, class)| class)
+            .collect()
+    }
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RngResponse {
+    Value(f64),
+    Count(u64),
+    #[serde(with = "Base64Standard")]
+    #[schemars(with = "String")]
+    Bytes(Vec<u8>),
+    Color(Color),
+    Palette(Vec<Color>),
+    Item(String),
+    Items(Vec<String>),
+    Uuid(String),
+    Password(String),
+}
+
+#[derive(Debug, Fail)]
+pub enum RequestError {
+    /// `field` is the path of the offending field in the request, like `parameters.std_dev`
+    #[fail(display = "{} {}", field, reason)]
+    InvalidParameter {
+        field: &'static str,
+        reason: &'static str,
+    },
+    #[fail(display = "a batch can hold at most {} requests", max)]
+    BatchTooLarge { max: usize },
+    #[fail(display = "a request can draw at most {} {} in total", max, what)]
+    TooLarge { what: &'static str, max: u64 },
+}
+
+pub fn ensure(valid: bool, field: &'static str, reason: &'static str) -> Result<(), RequestError> {
+    if valid {
+        Ok(())
+    } else {
+        Err(RequestError::InvalidParameter { field, reason })
+    }
+}
+
+impl RngRequest {
+    /// Checks the parameters up front, the `rand` constructors panic on invalid ones
+    pub fn validate(&self) -> Result<(), RequestError> {
+        match self {
+            RngRequest::Uniform { range } => ensure(
+                range.start < range.end,
+                "parameters.end",
+                "must be greater than start",
+            ),
+            RngRequest::Normal { mean, std_dev } | RngRequest::LogNormal { mean, std_dev } => {
+                ensure(
+                    mean.is_finite(),
+                    "parameters.mean",
+                    "must be a finite number",
+                )?;
+                ensure(
+                    std_dev.is_finite() && *std_dev >= 0.0,
+                    "parameters.std_dev",
+                    "must be a non-negative number",
+                )
+            }
+            RngRequest::Bernoulli { p } => ensure(
+                *p >= 0.0 && *p <= 1.0,
+                "parameters.p",
+                "must be between 0 and 1",
+            ),
+            RngRequest::Binomial { n, p } => {
+                ensure(
+                    *n <= MAX_BINOMIAL_N,
+                    "parameters.n",
+                    "must not exceed 1000000",
+                )?;
+                ensure(
+                    *p >= 0.0 && *p <= 1.0,
+                    "parameters.p",
+                    "must be between 0 and 1",
+                )
+            }
+            RngRequest::Exponential { lambda } | RngRequest::Poisson { lambda } => {
+                ensure(
+                    positive(*lambda),
+                    "parameters.lambda",
+                    "must be a positive number",
+                )?;
+                ensure(
+                    *lambda <= MAX_LAMBDA,
+                    "parameters.lambda",
+                    "must not exceed 1000000",
+                )
+            }
+            RngRequest::Gamma { shape, scale } => {
+                ensure(
+                    positive(*shape),
+                    "parameters.shape",
+                    "must be a positive number",
+                )?;
+                ensure(
+                    positive(*scale),
+                    "parameters.scale",
+                    "must be a positive number",
+                )
+            }
+            RngRequest::Beta { alpha, beta } => {
+                ensure(
+                    positive(*alpha),
+                    "parameters.alpha",
+                    "must be a positive number",
+                )?;
+                ensure(
+                    positive(*beta),
+                    "parameters.beta",
+                    "must be a positive number",
+                )
+            }
+            RngRequest::Shuffle { .. } | RngRequest::Color { .. } | RngRequest::Uuid => Ok(()),
+            RngRequest::Palette { size, .. } => ensure(
+                *size > 0 && *size <= MAX_PALETTE_SIZE,
+                "parameters.size",
+                "must be between 1 and 256",
+            ),
+            RngRequest::Choice { items } => {
+                ensure(!items.is_empty(), "parameters.items", "must not be empty")?;
+                let valid_weights = items
+                    .iter()
+                    .all(|item| item.weight.is_finite() && item.weight >= 0.0);
+                ensure(
+                    valid_weights,
+                    "parameters.items",
+                    "must all have non-negative weights",
+                )?;
+                let total: f64 = items.iter().map(|item| item.weight).sum();
+                ensure(
+                    total > 0.0 && total.is_finite(),
+                    "parameters.items",
+                    "must include a positive weight",
+                )
+            }
+            RngRequest::Sample { items, k } => ensure(
+                *k <= items.len(),
+                "parameters.k",
+                "must not exceed the number of items",
+            ),
+            RngRequest::Password { length, rules } => {
+                let classes = rules.classes().len();
+                ensure(
+                    classes > 0,
+                    "parameters",
+                    "must enable at least one character class",
+                )?;
+                ensure(
+                    *length >= classes,
+                    "parameters.length",
+                    "must fit one character of every enabled class",
+                )?;
+                ensure(
+                    *length <= MAX_PASSWORD_LENGTH,
+                    "parameters.length",
+                    "must not exceed 1024 characters",
+                )
+            }
+        }
+    }
+
+    /// Draws one value, the request has to pass `validate` first
+    pub fn generate<R: Rng>(&self, rng: &mut R) -> RngResponse {
+        match self {
+            RngRequest::Uniform { range } => {
+                let value = rng.sample(Uniform::from(range.clone())) as f64;
+                RngResponse::Value(value)
+            }
+            RngRequest::Normal { mean, std_dev } => {
+                let value = rng.sample(Normal::new(*mean, *std_dev));
+                RngResponse::Value(value)
+            }
+            RngRequest::Bernoulli { p } => {
+                let value = rng.sample(Bernoulli::new(*p)) as i8 as f64;
+                RngResponse::Value(value)
+            }
+            RngRequest::Exponential { lambda } => RngResponse::Value(rng.sample(Exp::new(*lambda))),
+            RngRequest::Poisson { lambda } => RngResponse::Count(rng.sample(Poisson::new(*lambda))),
+            RngRequest::Gamma { shape, scale } => {
+                RngResponse::Value(rng.sample(Gamma::new(*shape, *scale)))
+            }
+            RngRequest::Beta { alpha, beta } => {
+                // `rand` has no beta distribution, X / (X + Y) with X ~ Γ(α, 1) and Y ~ Γ(β, 1) is one
+                let x = rng.sample(Gamma::new(*alpha, 1.0));
+                let y = rng.sample(Gamma::new(*beta, 1.0));
+                // For tiny α and β both draws can underflow to 0. The distribution then sits at
+                // its ends, with 1 drawn in α / (α + β) of the cases
+                let value = if x + y > 0.0 {
+                    x / (x + y)
+                } else if rng.gen_bool(alpha / (alpha + beta)) {
+                    1.0
+                } else {
+                    0.0
+                };
+                RngResponse::Value(value)
+            }
+            RngRequest::Binomial { n, p } => RngResponse::Count(rng.sample(Binomial::new(*n, *p))),
+            RngRequest::LogNormal { mean, std_dev } => {
+                RngResponse::Value(rng.sample(LogNormal::new(*mean, *std_dev)))
+            }
+            RngRequest::Shuffle { data } => {
+                let mut data = data.clone();
+                rng.shuffle(&mut data);
+                RngResponse::Bytes(data)
+            }
+            RngRequest::Color { from, to } => RngResponse::Color(random_color(rng, from, to)),
+            RngRequest::Palette {
+                from,
+                to,
+                size,
+                gradient: false,
+            } => RngResponse::Palette((0..*size).map(|_| random_color(rng, from, to)).collect()),
+            RngRequest::Palette {
+                from,
+                to,
+                size,
+                gradient: true,
+            } => {
+                let steps = (*size - 1).max(1) as f64;
+                let colors = (0..*size).map(|i| from.mix(to, i as f64 / steps)).collect();
+                RngResponse::Palette(colors)
+            }
+            RngRequest::Choice { items } => {
+                let total: f64 = items.iter().map(|item| item.weight).sum();
+                let mut target = rng.gen_range(0.0, total);
+                // Rounding can leave `target` just past the last weight, which falls back to the
+                // last item that can actually be picked
+                let last = items.iter().rposition(|item| item.weight > 0.0).unwrap();
+                let index = items
+                    .iter()
+                    .position(|item| {
+                        target -= item.weight;
+                        item.weight > 0.0 && target < 0.0
+                    })
+                    .unwrap_or(last);
+                RngResponse::Item(items[index].item.clone())
+            }
+            RngRequest::Sample { items, k } => {
+                RngResponse::Items(seq::sample_slice(rng, items, *k))
+            }
+            RngRequest::Uuid => RngResponse::Uuid(uuid_v4(rng)),
+            RngRequest::Password { length, rules } => {
+                RngResponse::Password(password(rng, *length, rules))
+            }
+        }
+    }
+}
+
+fn positive(value: f64) -> bool {
+    value.is_finite() && value > 0.0
+}
+
+fn random_color<R: Rng>(rng: &mut R, from: &Color, to: &Color) -> Color {
+    Color {
+        red: rng.sample(color_range(from.red, to.red)),
+        green: rng.sample(color_range(from.green, to.green)),
+        blue: rng.sample(color_range(from.blue, to.blue)),
+        alpha: rng.sample(color_range(from.alpha, to.alpha)),
+    }
+}
+
+fn uuid_v4<R: Rng>(rng: &mut R) -> String {
+    let mut bytes: [u8; 16] = rng.gen();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: Vec<String> = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        hex[0..4].concat(),
+        hex[4..6].concat(),
+        hex[6..8].concat(),
+        hex[8..10].concat(),
+        hex[10..16].concat()
+    )
+}
+
+/// One character of every enabled class first, the rest from all of them, then shuffled so
+/// the guaranteed characters don't sit at the front
+fn password<R: Rng>(rng: &mut R, length: usize, rules: &PasswordRules) -> String {
+    let classes = rules.classes();
+    let all: Vec<u8> = classes.concat();
+    let mut chars: Vec<u8> = classes.iter().map(|class| pick(rng, class)).collect();
+    while chars.len() < length {
+        chars.push(pick(rng, &all));
+    }
+    rng.shuffle(&mut chars);
+    String::from_utf8(chars).expect("classes are ascii")
+}
+
+fn pick<R: Rng>(rng: &mut R, chars: &[u8]) -> u8 {
+    *rng.choose(chars).expect("classes aren't empty")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    fn invalid_field(request: RngRequest) -> &'static str {
+        match request.validate() {
+            Err(RequestError::InvalidParameter { field, .. }) => field,
+            _ => panic!("request should be invalid"),
+        }
+    }
+
+    #[test]
+    fn huge_parameters_are_rejected() {
+        let lambda = "parameters.lambda";
+        assert_eq!(invalid_field(RngRequest::Poisson { lambda: 1e308 }), lambda);
+        assert_eq!(
+            invalid_field(RngRequest::Exponential { lambda: 1e7 }),
+            lambda
+        );
+        let binomial = RngRequest::Binomial {
+            n: u64::MAX,
+            p: 0.5,
+        };
+        assert_eq!(invalid_field(binomial), "parameters.n");
+
+        assert!(RngRequest::Poisson { lambda: MAX_LAMBDA }
+            .validate()
+            .is_ok());
+        let binomial = RngRequest::Binomial {
+            n: MAX_BINOMIAL_N,
+            p: 0.3,
+        };
+        assert!(binomial.validate().is_ok());
+        let mut rng = thread_rng();
+        match binomial.generate(&mut rng) {
+            RngResponse::Count(count) => assert!(count <= MAX_BINOMIAL_N),
+            _ => panic!("binomial should give a count"),
+        }
+    }
+
+    #[test]
+    fn tiny_beta_parameters_stay_in_range() {
+        let mut rng = thread_rng();
+        let beta = RngRequest::Beta {
+            alpha: 1e-300,
+            beta: 1e-300,
+        };
+        assert!(beta.validate().is_ok());
+        for _ in 0..1000 {
+            match beta.generate(&mut rng) {
+                RngResponse::Value(value) => assert!((0.0..=1.0).contains(&value)),
+                _ => panic!("beta should give a value"),
+            }
+        }
+    }
+}
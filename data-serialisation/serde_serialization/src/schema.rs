@@ -0,0 +1,27 @@
+use crate::batch::{Reply, Sampling};
+use schemars::schema::RootSchema;
+use schemars::JsonSchema;
+
+/// The body of `POST /random`, `batch::handle` reads it from a generic value so this type only
+/// exists to describe it
+#[derive(JsonSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+enum RequestBody {
+    Single(Sampling),
+    Batch(Vec<Sampling>),
+}
+
+/// The schemas served at `GET /schema`
+#[derive(Serialize)]
+pub struct Schemas {
+    pub request: RootSchema,
+    pub response: RootSchema,
+}
+
+pub fn schemas() -> Schemas {
+    Schemas {
+        request: schemars::schema_for!(RequestBody),
+        response: schemars::schema_for!(Reply),
+    }
+}
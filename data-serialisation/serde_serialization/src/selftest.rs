@@ -0,0 +1,345 @@
+use crate::batch::Sampling;
+use crate::error::ApiError;
+use crate::rng::{ensure, RngRequest, RngResponse};
+use crate::stats::{self, Moments};
+use rand::Rng;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+pub const DEFAULT_SAMPLE_SIZE: u64 = 10_000;
+pub const MAX_SAMPLE_SIZE: u64 = 1_000_000;
+/// Chi-square needs a few expected observations per category to be meaningful
+const MIN_EXPECTED: f64 = 5.0;
+/// Discrete samples spread wider than this are too expensive to bin value by value
+const MAX_CATEGORIES: i64 = 1_000_000;
+
+/*
+    `POST /selftest` takes a request like `/random` does, with `count` as the sample size, and
+    checks the sample against the distribution it should follow:
+
+    - continuous distributions get a Kolmogorov–Smirnov test against their CDF
+    - discrete and categorical ones get a chi-square test, with sparse tails merged until every
+      category expects at least 5 observations
+
+    A tiny p-value means the generator doesn't produce what it claims.
+*/
+#[derive(Serialize)]
+pub struct Report {
+    pub sample_size: u64,
+    pub test: &'static str,
+    pub statistic: f64,
+    pub p_value: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub degrees_of_freedom: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moments: Option<Moments>,
+}
+
+enum Model {
+    Continuous(Box<dyn Fn(f64) -> f64>),
+    Discrete {
+        pmf: Box<dyn Fn(i64) -> f64>,
+        cdf: Box<dyn Fn(i64) -> f64>,
+    },
+    Categorical(Vec<(String, f64)>),
+}
+
+pub fn run<R: Rng>(sampling: Sampling, rng: &mut R) -> Result<Report, ApiError> {
+    let request = sampling.request;
+    request.validate()?;
+    let sample_size = sampling.count.unwrap_or(DEFAULT_SAMPLE_SIZE);
+    ensure(
+        (100..=MAX_SAMPLE_SIZE).contains(&sample_size),
+        "count",
+        "must be between 100 and 1000000",
+    )?;
+    let model = model(&request)?;
+
+    let report = match model {
+        Model::Continuous(cdf) => {
+            let mut sample = numeric_sample(&request, sample_size, rng)?;
+            let moments = stats::moments(&sample);
+            sample.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+            let n = sample.len() as f64;
+            let statistic = sample
+                .iter()
+                .enumerate()
+                .map(|(i, &x)| {
+                    let expected = cdf(x);
+                    let below = expected - i as f64 / n;
+                    let above = (i + 1) as f64 / n - expected;
+                    below.max(above)
+                })
+                .fold(0.0, f64::max);
+            Report {
+                sample_size,
+                test: "kolmogorov_smirnov",
+                statistic,
+                p_value: stats::kolmogorov_p_value(statistic, sample.len()),
+                degrees_of_freedom: None,
+                moments: Some(moments),
+            }
+        }
+        Model::Discrete { pmf, cdf } => {
+            let sample = numeric_sample(&request, sample_size, rng)?;
+            let moments = stats::moments(&sample);
+            let mut counts = HashMap::new();
+            for &x in &sample {
+                *counts.entry(x as i64).or_insert(0u64) += 1;
+            }
+            let min = *counts.keys().min().expect("the sample isn't empty");
+            let max = *counts.keys().max().expect("the sample isn't empty");
+            if max - min > MAX_CATEGORIES {
+                return Err(unsupported("the sample spreads over too many values"));
+            }
+            // The first and last categories also hold the tails beyond the observed values
+            let categories = (min..=max).map(|k| {
+                let probability = if min == max {
+                    1.0
+                } else if k == min {
+                    cdf(k)
+                } else if k == max {
+                    1.0 - cdf(k - 1)
+                } else {
+                    pmf(k)
+                };
+                (probability, counts.get(&k).cloned().unwrap_or(0))
+            });
+            chi_square(categories, sample_size, Some(moments))?
+        }
+        Model::Categorical(items) => {
+            let mut counts: HashMap<String, u64> = HashMap::new();
+            for _ in 0..sample_size {
+                if let RngResponse::Item(item) = request.generate(rng) {
+                    *counts.entry(item).or_insert(0) += 1;
+                }
+            }
+            let categories = items
+                .into_iter()
+                .map(|(item, probability)| (probability, counts.get(&item).cloned().unwrap_or(0)));
+            chi_square(categories, sample_size, None)?
+        }
+    };
+    Ok(report)
+}
+
+fn chi_square<I>(
+    categories: I,
+    sample_size: u64,
+    moments: Option<Moments>,
+) -> Result<Report, ApiError>
+where
+    I: Iterator<Item = (f64, u64)>,
+{
+    let n = sample_size as f64;
+    let mut merged: Vec<(f64, u64)> = Vec::new();
+    let mut pending = (0.0, 0);
+    for (probability, observed) in categories {
+        pending = (pending.0 + probability * n, pending.1 + observed);
+        if pending.0 >= MIN_EXPECTED {
+            merged.push(pending);
+            pending = (0.0, 0);
+        }
+    }
+    match merged.last_mut() {
+        Some(last) => *last = (last.0 + pending.0, last.1 + pending.1),
+        None => merged.push(pending),
+    }
+    if merged.len() < 2 {
+        return Err(unsupported(
+            "a chi-square test needs at least two categories",
+        ));
+    }
+
+    let statistic = merged
+        .iter()
+        .map(|&(expected, observed)| {
+            let difference = observed as f64 - expected;
+            difference * difference / expected
+        })
+        .sum();
+    let degrees_of_freedom = merged.len() - 1;
+    Ok(Report {
+        sample_size,
+        test: "chi_square",
+        statistic,
+        p_value: stats::chi_square_p_value(statistic, degrees_of_freedom),
+        degrees_of_freedom: Some(degrees_of_freedom),
+        moments,
+    })
+}
+
+/// Extreme parameters can overflow, such as a log-normal with a large mean, and no test or
+/// moment means anything over infinite values
+fn numeric_sample<R: Rng>(
+    request: &RngRequest,
+    sample_size: u64,
+    rng: &mut R,
+) -> Result<Vec<f64>, ApiError> {
+    let sample: Vec<f64> = (0..sample_size)
+        .filter_map(|_| match request.generate(rng) {
+            RngResponse::Value(value) => Some(value),
+            RngResponse::Count(count) => Some(count as f64),
+            _ => None,
+        })
+        .collect();
+    if sample.iter().all(|x| x.is_finite()) {
+        Ok(sample)
+    } else {
+        Err(ApiError::new(
+            "sample_not_finite",
+            "the sample holds values that aren't finite numbers",
+        )
+        .at("parameters"))
+    }
+}
+
+fn model(request: &RngRequest) -> Result<Model, ApiError> {
+    let model = match *request {
+        RngRequest::Uniform { ref range } => {
+            let (start, end) = (i64::from(range.start), i64::from(range.end));
+            let width = (end - start) as f64;
+            Model::Discrete {
+                pmf: Box::new(move |k| {
+                    if k >= start && k < end {
+                        1.0 / width
+                    } else {
+                        0.0
+                    }
+                }),
+                cdf: Box::new(move |k| {
+                    if k < start {
+                        0.0
+                    } else if k >= end {
+                        1.0
+                    } else {
+                        (k - start + 1) as f64 / width
+                    }
+                }),
+            }
+        }
+        RngRequest::Bernoulli { p } => Model::Discrete {
+            pmf: Box::new(move |k| match k {
+                0 => 1.0 - p,
+                1 => p,
+                _ => 0.0,
+            }),
+            cdf: Box::new(move |k| match k {
+                k if k < 0 => 0.0,
+                0 => 1.0 - p,
+                _ => 1.0,
+            }),
+        },
+        RngRequest::Poisson { lambda } => Model::Discrete {
+            pmf: Box::new(move |k| {
+                let k = k as f64;
+                (k * lambda.ln() - lambda - stats::ln_gamma(k + 1.0)).exp()
+            }),
+            cdf: Box::new(move |k| {
+                if k < 0 {
+                    0.0
+                } else {
+                    stats::gamma_q(k as f64 + 1.0, lambda)
+                }
+            }),
+        },
+        RngRequest::Binomial { n, p } => {
+            if p == 0.0 || p == 1.0 {
+                return Err(unsupported("a degenerate distribution can't be tested"));
+            }
+            let n = n as f64;
+            Model::Discrete {
+                pmf: Box::new(move |k| {
+                    let k = k as f64;
+                    let ln_choose = stats::ln_gamma(n + 1.0)
+                        - stats::ln_gamma(k + 1.0)
+                        - stats::ln_gamma(n - k + 1.0);
+                    (ln_choose + k * p.ln() + (n - k) * (1.0 - p).ln()).exp()
+                }),
+                cdf: Box::new(move |k| {
+                    let k = k as f64;
+                    if k < 0.0 {
+                        0.0
+                    } else if k >= n {
+                        1.0
+                    } else {
+                        stats::beta_i(n - k, k + 1.0, 1.0 - p)
+                    }
+                }),
+            }
+        }
+        RngRequest::Normal { mean, std_dev } => {
+            if std_dev == 0.0 {
+                return Err(unsupported("a degenerate distribution can't be tested"));
+            }
+            Model::Continuous(Box::new(move |x| stats::normal_cdf((x - mean) / std_dev)))
+        }
+        RngRequest::LogNormal { mean, std_dev } => {
+            if std_dev == 0.0 {
+                return Err(unsupported("a degenerate distribution can't be tested"));
+            }
+            Model::Continuous(Box::new(move |x| {
+                if x <= 0.0 {
+                    0.0
+                } else {
+                    stats::normal_cdf((x.ln() - mean) / std_dev)
+                }
+            }))
+        }
+        RngRequest::Exponential { lambda } => Model::Continuous(Box::new(move |x| {
+            if x <= 0.0 {
+                0.0
+            } else {
+                1.0 - (-lambda * x).exp()
+            }
+        })),
+        RngRequest::Gamma { shape, scale } => {
+            Model::Continuous(Box::new(move |x| stats::gamma_p(shape, x / scale)))
+        }
+        RngRequest::Beta { alpha, beta } => {
+            Model::Continuous(Box::new(move |x| stats::beta_i(alpha, beta, x)))
+        }
+        RngRequest::Choice { ref items } => {
+            let total: f64 = items.iter().map(|item| item.weight).sum();
+            let mut probabilities: Vec<(String, f64)> = Vec::new();
+            // Items sharing a name are indistinguishable in the sample
+            for item in items {
+                match probabilities
+                    .iter_mut()
+                    .find(|(name, _)| *name == item.item)
+                {
+                    Some(entry) => entry.1 += item.weight / total,
+                    None => probabilities.push((item.item.clone(), item.weight / total)),
+                }
+            }
+            Model::Categorical(probabilities)
+        }
+        _ => {
+            return Err(unsupported(
+                "only numeric distributions and choices can be tested",
+            ))
+        }
+    };
+    Ok(model)
+}
+
+fn unsupported(message: &str) -> ApiError {
+    ApiError::new("unsupported_distribution", message).at("distribution")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    #[test]
+    fn overflowing_samples_are_refused() {
+        let sampling = |mean| Sampling {
+            request: RngRequest::LogNormal { mean, std_dev: 1.0 },
+            count: Some(100),
+        };
+        let err = run(sampling(1000.0), &mut thread_rng()).err().unwrap();
+        assert_eq!(err.code, "sample_not_finite");
+        assert!(run(sampling(0.0), &mut thread_rng()).is_ok());
+    }
+}
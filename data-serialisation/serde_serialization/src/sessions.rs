@@ -0,0 +1,109 @@
+use crate::error::ApiError;
+use rand::distributions::Alphanumeric;
+use rand::prng::ChaChaRng;
+use rand::{thread_rng, Rng, SeedableRng};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub const MAX_SESSIONS: usize = 1000;
+const DEFAULT_TTL: u64 = 600;
+const MAX_TTL: u64 = 24 * 60 * 60;
+
+/*
+    A session owns a seeded ChaCha generator, so the same seed replays the same values for the
+    same sequence of requests:
+
+    POST   /sessions               - {"seed": 42, "ttl": 600}, both optional
+    POST   /random?session={id}    - draws from the session instead of the thread's generator
+    DELETE /sessions/{id}
+
+    Sessions expire after `ttl` seconds without a draw. Expired ones are dropped whenever the
+    table is touched, a full table refuses new sessions.
+*/
+#[derive(Default, Deserialize)]
+pub struct SessionRequest {
+    pub seed: Option<u64>,
+    pub ttl: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct SessionCreated {
+    pub id: String,
+    pub seed: u64,
+    pub ttl: u64,
+}
+
+struct Session {
+    rng: ChaChaRng,
+    ttl: Duration,
+    expires: Instant,
+}
+
+/// Each session has a lock of its own, so drawing from one doesn't hold up the others
+#[derive(Default)]
+pub struct Sessions {
+    sessions: Mutex<HashMap<String, Arc<Mutex<Session>>>>,
+}
+
+impl Sessions {
+    pub fn create(&self, request: SessionRequest) -> Result<SessionCreated, ApiError> {
+        let ttl = request.ttl.unwrap_or(DEFAULT_TTL);
+        if ttl == 0 || ttl > MAX_TTL {
+            let error = ApiError::new("invalid_parameter", "ttl must be between 1 and 86400");
+            return Err(error.at("ttl"));
+        }
+        let seed = request.seed.unwrap_or_else(|| thread_rng().gen());
+
+        let now = Instant::now();
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|_, session| is_live(session, now));
+        if sessions.len() >= MAX_SESSIONS {
+            return Err(ApiError::new(
+                "too_many_sessions",
+                "the session limit is reached, try again later",
+            ));
+        }
+
+        let id: String = thread_rng().sample_iter(&Alphanumeric).take(20).collect();
+        let ttl_duration = Duration::from_secs(ttl);
+        let session = Session {
+            rng: ChaChaRng::seed_from_u64(seed),
+            ttl: ttl_duration,
+            expires: now + ttl_duration,
+        };
+        sessions.insert(id.clone(), Arc::new(Mutex::new(session)));
+        Ok(SessionCreated { id, seed, ttl })
+    }
+
+    /// Runs `f` with the session's generator, only the session stays locked meanwhile so
+    /// concurrent requests to it take turns instead of interleaving their draws
+    pub fn draw<T, F>(&self, id: &str, f: F) -> Result<T, ApiError>
+    where
+        F: FnOnce(&mut ChaChaRng) -> Result<T, ApiError>,
+    {
+        let now = Instant::now();
+        let session = {
+            let mut sessions = self.sessions.lock().unwrap();
+            sessions.retain(|_, session| is_live(session, now));
+            sessions.get(id).cloned()
+        };
+        let session =
+            session.ok_or_else(|| ApiError::new("session_not_found", "no such session"))?;
+        let mut session = session.lock().unwrap();
+        session.expires = Instant::now() + session.ttl;
+        f(&mut session.rng)
+    }
+
+    pub fn remove(&self, id: &str) -> bool {
+        self.sessions.lock().unwrap().remove(id).is_some()
+    }
+}
+
+/// A session busy drawing is live, it is about to be renewed
+fn is_live(session: &Mutex<Session>, now: Instant) -> bool {
+    match session.try_lock() {
+        Ok(session) => session.expires > now,
+        Err(_) => true,
+    }
+}
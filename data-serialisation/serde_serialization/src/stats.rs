@@ -0,0 +1,312 @@
+//! Special functions behind the goodness-of-fit tests, following Numerical Recipes
+
+use std::f64::consts::PI;
+
+const EPS: f64 = 1e-14;
+const FPMIN: f64 = 1e-300;
+const MAX_ITERATIONS: usize = 1000;
+
+/// ln Γ(x), Lanczos approximation with g = 7
+pub fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // Reflection formula
+        return (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let sum = COEFFICIENTS
+        .iter()
+        .enumerate()
+        .skip(1)
+        .fold(COEFFICIENTS[0], |sum, (i, c)| sum + c / (x + i as f64));
+    0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// The regularized lower incomplete gamma function P(a, x)
+pub fn gamma_p(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        0.0
+    } else if x < a + 1.0 {
+        gamma_series(a, x)
+    } else {
+        1.0 - gamma_continued_fraction(a, x)
+    }
+}
+
+/// The regularized upper incomplete gamma function Q(a, x) = 1 - P(a, x)
+pub fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        1.0
+    } else if x < a + 1.0 {
+        1.0 - gamma_series(a, x)
+    } else {
+        gamma_continued_fraction(a, x)
+    }
+}
+
+fn gamma_series(a: f64, x: f64) -> f64 {
+    let mut ap = a;
+    let mut term = 1.0 / a;
+    let mut sum = term;
+    for _ in 0..MAX_ITERATIONS {
+        ap += 1.0;
+        term *= x / ap;
+        sum += term;
+        if term.abs() < sum.abs() * EPS {
+            break;
+        }
+    }
+    sum * (-x + a * x.ln() - ln_gamma(a)).exp()
+}
+
+/// Q(a, x) by Lentz's method
+fn gamma_continued_fraction(a: f64, x: f64) -> f64 {
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / FPMIN;
+    let mut d = 1.0 / b;
+    let mut h = d;
+    for i in 1..MAX_ITERATIONS {
+        let i = i as f64;
+        let an = -i * (i - a);
+        b += 2.0;
+        d = not_tiny(an * d + b);
+        c = not_tiny(b + an / c);
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPS {
+            break;
+        }
+    }
+    (-x + a * x.ln() - ln_gamma(a)).exp() * h
+}
+
+/// The regularized incomplete beta function I_x(a, b)
+pub fn beta_i(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    // The continued fraction converges quickly on this side, the other uses the symmetry
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    let (qab, qap, qam) = (a + b, a + 1.0, a - 1.0);
+    let mut c = 1.0;
+    let mut d = 1.0 / not_tiny(1.0 - qab * x / qap);
+    let mut h = d;
+    for m in 1..MAX_ITERATIONS {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+        let aa = m * (b - m) * x / ((qam + m2) * (a + m2));
+        d = 1.0 / not_tiny(1.0 + aa * d);
+        c = not_tiny(1.0 + aa / c);
+        h *= d * c;
+        let aa = -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2));
+        d = 1.0 / not_tiny(1.0 + aa * d);
+        c = not_tiny(1.0 + aa / c);
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPS {
+            break;
+        }
+    }
+    h
+}
+
+fn not_tiny(value: f64) -> f64 {
+    if value.abs() < FPMIN {
+        FPMIN
+    } else {
+        value
+    }
+}
+
+/// The standard normal CDF, erf(x) is P(1/2, x²)
+pub fn normal_cdf(z: f64) -> f64 {
+    let half = 0.5 * gamma_p(0.5, z * z / 2.0);
+    if z >= 0.0 {
+        0.5 + half
+    } else {
+        0.5 - half
+    }
+}
+
+/// P-value of a chi-square statistic with `df` degrees of freedom
+pub fn chi_square_p_value(statistic: f64, df: usize) -> f64 {
+    gamma_q(df as f64 / 2.0, statistic / 2.0)
+}
+
+/// P-value of the Kolmogorov–Smirnov statistic `d` for a sample of `n`
+pub fn kolmogorov_p_value(d: f64, n: usize) -> f64 {
+    let sqrt_n = (n as f64).sqrt();
+    let lambda = (sqrt_n + 0.12 + 0.11 / sqrt_n) * d;
+    let mut sign = 2.0;
+    let mut sum = 0.0;
+    let mut previous = 0.0;
+    for j in 1..=100 {
+        let j = f64::from(j);
+        let term = sign * (-2.0 * j * j * lambda * lambda).exp();
+        sum += term;
+        if term.abs() <= 0.001 * previous || term.abs() <= 1e-8 * sum {
+            return sum;
+        }
+        sign = -sign;
+        previous = term.abs();
+    }
+    // The series only fails to converge for tiny statistics
+    1.0
+}
+
+#[derive(Serialize)]
+pub struct Moments {
+    pub mean: f64,
+    pub variance: f64,
+    pub skewness: f64,
+    /// Excess kurtosis, 0 for a normal distribution
+    pub kurtosis: f64,
+}
+
+pub fn moments(sample: &[f64]) -> Moments {
+    let n = sample.len() as f64;
+    let mean = sample.iter().sum::<f64>() / n;
+    let (m2, m3, m4) = sample.iter().fold((0.0, 0.0, 0.0), |(m2, m3, m4), x| {
+        let d = x - mean;
+        (m2 + d * d, m3 + d * d * d, m4 + d * d * d * d)
+    });
+    let (m2, m3, m4) = (m2 / n, m3 / n, m4 / n);
+    Moments {
+        mean,
+        variance: m2 * n / (n - 1.0),
+        skewness: m3 / m2.powf(1.5),
+        kurtosis: m4 / (m2 * m2) - 3.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{} is not within {} of {}",
+            actual,
+            tolerance,
+            expected
+        );
+    }
+
+    #[test]
+    fn ln_gamma_known_values() {
+        // Γ(n) = (n - 1)!, Γ(1/2) = √π, Γ(1/10) from Abramowitz & Stegun table 6.1
+        assert_close(ln_gamma(1.0), 0.0, 1e-12);
+        assert_close(ln_gamma(2.0), 0.0, 1e-12);
+        assert_close(ln_gamma(10.0), 362_880f64.ln(), 1e-10);
+        assert_close(ln_gamma(0.5), PI.sqrt().ln(), 1e-12);
+        assert_close(ln_gamma(0.1), 9.513_507_698_668_732f64.ln(), 1e-10);
+        assert_close(ln_gamma(101.0), 363.739_375_555_563_5, 1e-8);
+    }
+
+    #[test]
+    fn incomplete_gamma_known_values() {
+        // P(1, x) = 1 - e^-x
+        for &x in &[0.1f64, 1.0, 5.0, 20.0] {
+            assert_close(gamma_p(1.0, x), 1.0 - (-x).exp(), 1e-12);
+            assert_close(gamma_q(1.0, x), (-x).exp(), 1e-12);
+        }
+        assert_eq!(gamma_p(2.0, 0.0), 0.0);
+        assert_eq!(gamma_q(2.0, 0.0), 1.0);
+        // P(3, x) = 1 - e^-x (1 + x + x²/2), on both sides of the series / fraction switch
+        assert_close(gamma_p(3.0, 2.0), 1.0 - 5.0 * (-2f64).exp(), 1e-12);
+        assert_close(gamma_p(3.0, 6.0), 1.0 - 25.0 * (-6f64).exp(), 1e-12);
+    }
+
+    #[test]
+    fn normal_cdf_known_values() {
+        assert_close(normal_cdf(0.0), 0.5, 1e-12);
+        assert_close(normal_cdf(1.0), 0.841_344_746_068_542_9, 1e-10);
+        assert_close(normal_cdf(-1.0), 0.158_655_253_931_457_05, 1e-10);
+        assert_close(normal_cdf(1.96), 0.975_002_104_851_779_5, 1e-10);
+        assert_close(normal_cdf(-3.0), 0.001_349_898_031_630_094_6, 1e-12);
+    }
+
+    #[test]
+    fn incomplete_beta_known_values() {
+        assert_eq!(beta_i(2.0, 3.0, 0.0), 0.0);
+        assert_eq!(beta_i(2.0, 3.0, 1.0), 1.0);
+        // I_x(1, 1) = x and the symmetry I_½(a, a) = ½
+        assert_close(beta_i(1.0, 1.0, 0.3), 0.3, 1e-12);
+        assert_close(beta_i(7.5, 7.5, 0.5), 0.5, 1e-12);
+        // For integers I_x(a, b) is a binomial tail, P(X ≥ 2) for X ~ B(4, 0.3)
+        assert_close(beta_i(2.0, 3.0, 0.3), 0.3483, 1e-12);
+        assert_close(beta_i(2.0, 3.0, 0.8), 0.9728, 1e-12);
+    }
+
+    #[test]
+    fn chi_square_critical_values() {
+        // Upper critical values from the NIST/SEMATECH e-Handbook, table 1.3.6.7.4
+        let table = [
+            (1, 3.841, 0.05),
+            (1, 6.635, 0.01),
+            (2, 5.991, 0.05),
+            (5, 11.070, 0.05),
+            (10, 18.307, 0.05),
+            (10, 23.209, 0.01),
+            (30, 43.773, 0.05),
+            (100, 124.342, 0.05),
+        ];
+        for &(df, statistic, p_value) in table.iter() {
+            assert_close(chi_square_p_value(statistic, df), p_value, 2e-4);
+        }
+    }
+
+    #[test]
+    fn kolmogorov_critical_values() {
+        // Asymptotic critical values λ of the Kolmogorov distribution, for α = 0.2, 0.1, 0.05,
+        // 0.01 and 0.001
+        let table = [
+            (1.0727, 0.2),
+            (1.2238, 0.1),
+            (1.3581, 0.05),
+            (1.6276, 0.01),
+            (1.9495, 0.001),
+        ];
+        let n = 10_000;
+        let sqrt_n = (n as f64).sqrt();
+        for &(lambda, p_value) in table.iter() {
+            let d = lambda / (sqrt_n + 0.12 + 0.11 / sqrt_n);
+            assert_close(kolmogorov_p_value(d, n), p_value, 1e-4);
+        }
+        assert_eq!(kolmogorov_p_value(0.0, n), 1.0);
+    }
+
+    #[test]
+    fn moments_of_a_small_sample() {
+        let moments = moments(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+        assert_close(moments.mean, 5.0, 1e-12);
+        assert_close(moments.variance, 32.0 / 7.0, 1e-12);
+        assert_close(moments.skewness, 0.65625, 1e-12);
+        assert_close(moments.kurtosis, 2.78125 - 3.0, 1e-12);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::batch::Sampling;
+use crate::error::ApiError;
+use crate::format::accepted_media_types;
+use crate::rng::{ensure, RngRequest};
+use futures::{Async, Poll, Stream};
+use hyper::Chunk;
+use rand::rngs::StdRng;
+use rand::FromEntropy;
+use std::io::{Error, ErrorKind};
+
+pub const MAX_STREAM_COUNT: u64 = 1_000_000_000;
+/// Samples serialized per chunk, small enough to keep the memory use flat
+const CHUNK_SAMPLES: u64 = 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamFormat {
+    /// One JSON document per line
+    Ndjson,
+    /// CBOR items back to back, as in RFC 8742
+    CborSeq,
+}
+
+impl StreamFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "ndjson" | "json" => Some(StreamFormat::Ndjson),
+            "cbor-seq" | "cbor" => Some(StreamFormat::CborSeq),
+            _ => None,
+        }
+    }
+
+    pub fn from_media_type(media_type: &str) -> Option<Self> {
+        match media_type.to_ascii_lowercase().as_str() {
+            "application/x-ndjson" | "application/*" | "*/*" => Some(StreamFormat::Ndjson),
+            "application/cbor-seq" => Some(StreamFormat::CborSeq),
+            _ => None,
+        }
+    }
+
+    pub fn media_type(self) -> &'static str {
+        match self {
+            StreamFormat::Ndjson => "application/x-ndjson",
+            StreamFormat::CborSeq => "application/cbor-seq",
+        }
+    }
+}
+
+/// Same rules as `format::negotiate`, a request with neither gets NDJSON
+pub fn negotiate(query_format: Option<&str>, accept: Option<&str>) -> Option<StreamFormat> {
+    if let Some(name) = query_format {
+        return StreamFormat::from_name(name);
+    }
+    match accept {
+        Some(accept) if !accept.trim().is_empty() => accepted_media_types(accept)
+            .into_iter()
+            .filter_map(StreamFormat::from_media_type)
+            .next(),
+        _ => Some(StreamFormat::Ndjson),
+    }
+}
+
+/*
+    Generates `count` samples lazily, a chunk at a time. Hyper only polls a body stream when the
+    connection is ready to take more data, so a slow client slows the generation down instead
+    of samples piling up in memory.
+*/
+pub struct Samples {
+    request: RngRequest,
+    remaining: u64,
+    format: StreamFormat,
+    rng: StdRng,
+}
+
+impl Samples {
+    pub fn new(sampling: Sampling, format: StreamFormat) -> Result<Self, ApiError> {
+        let count = match sampling.count {
+            Some(count) => count,
+            None => {
+                return Err(ApiError::new("invalid_request", "missing field `count`").at("count"));
+            }
+        };
+        sampling.request.validate()?;
+        ensure(
+            count > 0 && count <= MAX_STREAM_COUNT,
+            "count",
+            "must be between 1 and 1000000000",
+        )?;
+        Ok(Samples {
+            request: sampling.request,
+            remaining: count,
+            format,
+            rng: StdRng::from_entropy(),
+        })
+    }
+}
+
+impl Stream for Samples {
+    type Item = Chunk;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Chunk>, Error> {
+        if self.remaining == 0 {
+            return Ok(Async::Ready(None));
+        }
+        let samples = self.remaining.min(CHUNK_SAMPLES);
+        let mut buffer = Vec::new();
+        for _ in 0..samples {
+            let resp = self.request.generate(&mut self.rng);
+            match self.format {
+                StreamFormat::Ndjson => {
+                    serde_json::to_writer(&mut buffer, &resp).map_err(other)?;
+                    buffer.push(b'\n');
+                }
+                StreamFormat::CborSeq => {
+                    serde_cbor::to_writer(&mut buffer, &resp).map_err(other)?
+                }
+            }
+        }
+        self.remaining -= samples;
+        Ok(Async::Ready(Some(buffer.into())))
+    }
+}
+
+fn other<E: ToString>(err: E) -> Error {
+    Error::new(ErrorKind::Other, err.to_string())
+}
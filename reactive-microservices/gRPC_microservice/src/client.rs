@@ -1,10 +1,88 @@
 use failure::Error;
+use futures::Stream;
+use grpc_microservice::ring::{Candidate, RollCall};
+use grpc_microservice::tls::TlsConfig;
 use grpc_microservice::Remote;
 use std::env;
+use std::io::{self, BufRead};
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+/// How long to wait for a roll call to go around the ring twice
+const TALLY_DEADLINE: Duration = Duration::from_secs(60);
+/// How long to wait for the token
+const LOCK_DEADLINE: Duration = Duration::from_secs(60);
 
 fn main() -> Result<(), Error> {
     let next = env::var("NEXT")?.parse()?;
-    let remote = Remote::new(next)?;
-    remote.start_roll_call()?;
+    let tls = match TlsConfig::from_env()? {
+        Some(tls) => Some(tls.client()?),
+        None => None,
+    };
+    let remote = Remote::connect(next, tls.as_ref())?;
+    let mut runtime = Runtime::new()?;
+    match env::args().nth(1) {
+        Some(ref command) if command == "leave" => {
+            runtime.block_on(remote.leave(next, None))?;
+        }
+        Some(ref command) if command == "elect" => {
+            runtime.block_on(remote.elect(Candidate::new()))?;
+        }
+        Some(ref command) if command == "leader" => match runtime.block_on(remote.get_leader())? {
+            Some(leader) => println!("{} ({})", leader.get_address(), leader.get_id()),
+            None => println!("no leader"),
+        },
+        Some(ref command) if command == "lock" => {
+            let lock = runtime.block_on(remote.with_deadline(LOCK_DEADLINE).acquire())?;
+            println!(
+                "holding {} for up to {}s, press enter to release",
+                lock.get_id(),
+                lock.get_lease_secs()
+            );
+            io::stdin().lock().lines().next();
+            runtime.block_on(remote.release(lock))?;
+        }
+        Some(ref command) if command == "status" => {
+            let status = runtime.block_on(remote.status())?;
+            println!("address: {}", status.get_address());
+            println!("in roll calls: {}", status.get_roll_calls().join(", "));
+            println!("in election: {}", status.get_in_election());
+            println!("successor: {}", status.get_successor());
+            println!("leader: {}", status.get_leader());
+            for counter in status.get_handled() {
+                println!("{}: {}", counter.get_action(), counter.get_count());
+            }
+            if !status.get_last_error().is_empty() {
+                println!(
+                    "last error: {} (at {})",
+                    status.get_last_error(),
+                    status.get_last_error_at()
+                );
+            }
+        }
+        Some(ref command) if command == "watch" => {
+            let events = remote.watch_events().for_each(|event| {
+                println!(
+                    "{} {} {}",
+                    event.get_node(),
+                    event.get_kind(),
+                    event.get_detail()
+                );
+                Ok(())
+            });
+            runtime.block_on(events)?;
+        }
+        _ => {
+            let call = runtime.block_on(remote.start_roll_call(RollCall::new()))?;
+            let tally = remote
+                .with_deadline(TALLY_DEADLINE)
+                .await_tally(call.get_id());
+            let tally = runtime.block_on(tally)?;
+            println!("{}: {} nodes", tally.get_id(), tally.get_marked().len());
+            for node in tally.get_marked() {
+                println!("{}", node);
+            }
+        }
+    }
     Ok(())
 }
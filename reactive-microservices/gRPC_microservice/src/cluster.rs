@@ -0,0 +1,334 @@
+use crate::node::{Config, Node, SUCCESSORS};
+use crate::ring::{
+    Candidate, Empty, Event, JoinReply, JoinRequest, LeaveRequest, Lock, NodeStatus, RollCall,
+    Successors, Tally, Token, TokenProbe, TokenRequest,
+};
+use crate::ring_grpc::Ring;
+use crate::tls::{ClientTls, TlsConfig};
+use crate::Remote;
+
+use failure::Error;
+use futures::sync::oneshot;
+use futures::{future, Future};
+use grpc::{RequestOptions, SingleResponse, StreamingResponse};
+use std::cmp;
+use std::net::{SocketAddr, TcpListener};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::runtime::{Runtime, TaskExecutor};
+use tokio::timer::Delay;
+
+/// How long the nodes get to agree after a change, on successors or on a leader
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A ring of nodes running in one process on ephemeral ports, for tests and experiments.
+/// Nodes keep their index and address when killed, so that they can be restarted
+pub struct Cluster {
+    addrs: Vec<SocketAddr>,
+    nodes: Vec<Option<Node>>,
+    faults: Vec<Arc<Mutex<Option<Fault>>>>,
+    tls: Option<TlsConfig>,
+    client_tls: Option<ClientTls>,
+    runtime: Runtime,
+}
+
+impl Cluster {
+    /// Starts `size` nodes, each with the next one as its successor
+    pub fn start(size: usize) -> Result<Self, Error> {
+        Self::start_with_tls(size, None)
+    }
+
+    /// Starts `size` nodes talking mutual TLS with `tls` if given, every node uses the same
+    /// certificate
+    pub fn start_with_tls(size: usize, tls: Option<TlsConfig>) -> Result<Self, Error> {
+        Self::start_nodes(size, tls, None)
+    }
+
+    /// Starts `size` nodes where the node at `slow` confirms messages `delay` after it takes
+    /// them
+    pub fn start_with_slow_node(size: usize, slow: usize, delay: Duration) -> Result<Self, Error> {
+        Self::start_nodes(size, None, Some((slow, Fault::Slow(delay))))
+    }
+
+    fn start_nodes(
+        size: usize,
+        tls: Option<TlsConfig>,
+        fault: Option<(usize, Fault)>,
+    ) -> Result<Self, Error> {
+        let addrs = (0..size)
+            .map(|_| free_addr())
+            .collect::<Result<Vec<_>, _>>()?;
+        let faults = (0..size)
+            .map(|index| match fault {
+                Some((faulty, fault)) if faulty == index => Arc::new(Mutex::new(Some(fault))),
+                _ => Arc::new(Mutex::new(None)),
+            })
+            .collect::<Vec<_>>();
+        let runtime = Runtime::new()?;
+        let mut nodes = Vec::with_capacity(size);
+        for (index, &addr) in addrs.iter().enumerate() {
+            let mut config = Config::new(addr);
+            config.next = Some(addrs[(index + 1) % size]);
+            config.tls = tls.clone();
+            nodes.push(Some(start_node(config, &faults[index], &runtime)?));
+        }
+        let client_tls = match tls {
+            Some(ref tls) => Some(tls.client()?),
+            None => None,
+        };
+
+        let cluster = Self {
+            addrs,
+            nodes,
+            faults,
+            tls,
+            client_tls,
+            runtime,
+        };
+        cluster.settle()?;
+        Ok(cluster)
+    }
+
+    pub fn addresses(&self) -> &[SocketAddr] {
+        &self.addrs
+    }
+
+    /// The node at `index`, unless it was killed
+    pub fn node(&self, index: usize) -> Option<&Node> {
+        self.nodes.get(index).and_then(Option::as_ref)
+    }
+
+    /// The nodes still running
+    pub fn nodes(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.iter().filter_map(Option::as_ref)
+    }
+
+    /// Makes the node at `index` fail the way `fault` says from now on, or work again
+    pub fn set_fault(&self, index: usize, fault: Option<Fault>) {
+        *self.faults[index].lock().unwrap() = fault;
+    }
+
+    pub fn kill(&mut self, index: usize) -> Result<(), Error> {
+        match self.nodes[index].take() {
+            Some(node) => node.kill(),
+            None => Ok(()),
+        }
+    }
+
+    /// Starts a killed node again on its old address, joining after the nearest running node
+    /// before it
+    pub fn restart(&mut self, index: usize) -> Result<(), Error> {
+        if self.nodes[index].is_some() {
+            return Ok(());
+        }
+        let size = self.nodes.len();
+        let mut config = Config::new(self.addrs[index]);
+        config.tls = self.tls.clone();
+        config.join = (1..size)
+            .map(|offset| (index + size - offset) % size)
+            .find(|&other| self.nodes[other].is_some())
+            .map(|other| self.addrs[other]);
+        self.nodes[index] = Some(start_node(config, &self.faults[index], &self.runtime)?);
+        self.settle()
+    }
+
+    /// A client of the node at `index`, over TLS if the cluster uses it
+    pub fn connect(&self, index: usize) -> Result<Remote, Error> {
+        Ok(Remote::connect(
+            self.addrs[index],
+            self.client_tls.as_ref(),
+        )?)
+    }
+
+    /// Waits until every running node knows as many running successors as it keeps, so that
+    /// the ring routes around the next node killed
+    pub fn settle(&self) -> Result<(), Error> {
+        let running = self.nodes().map(Node::addr).collect::<Vec<_>>();
+        // A node on its own is its only successor
+        let expected = match running.len() {
+            0 => return Ok(()),
+            1 => 1,
+            size => cmp::min(size - 1, SUCCESSORS),
+        };
+        self.wait_for("the ring did not settle", |cluster| {
+            cluster.nodes().all(|node| {
+                let successors = node.successors();
+                successors.len() == expected && successors.iter().all(|addr| running.contains(addr))
+            })
+        })
+    }
+
+    /// Starts an election at the node at `index`, returns the leader once every running node
+    /// knows it
+    pub fn elect(&mut self, index: usize) -> Result<SocketAddr, Error> {
+        let remote = self.connect(index)?;
+        self.runtime.block_on(remote.elect(Candidate::new()))?;
+        self.wait_for("no leader was elected", |cluster| {
+            let mut leaders = cluster.nodes().map(Node::leader);
+            match leaders.next() {
+                Some(Some(first)) => leaders.all(|leader| leader.as_ref() == Some(&first)),
+                _ => false,
+            }
+        })?;
+        let leader = self.nodes().next().and_then(Node::leader);
+        let leader = leader.ok_or_else(|| failure::err_msg("no leader was elected"))?;
+        Ok(leader.get_address().parse()?)
+    }
+
+    fn wait_for<F: Fn(&Self) -> bool>(&self, failure: &'static str, done: F) -> Result<(), Error> {
+        let started = Instant::now();
+        while !done(self) {
+            if started.elapsed() > SETTLE_TIMEOUT {
+                return Err(failure::err_msg(failure));
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        Ok(())
+    }
+
+    /// Runs a roll call initiated by the node at `index` and waits up to `deadline` for its
+    /// tally
+    pub fn roll_call(&mut self, index: usize, deadline: Duration) -> Result<Tally, Error> {
+        let remote = self.connect(index)?;
+        let call = self
+            .runtime
+            .block_on(remote.start_roll_call(RollCall::new()))?;
+        let tally = remote.with_deadline(deadline).await_tally(call.get_id());
+        Ok(self.runtime.block_on(tally)?)
+    }
+}
+
+/// Delays for slow nodes run on `runtime`
+fn start_node(
+    config: Config,
+    fault: &Arc<Mutex<Option<Fault>>>,
+    runtime: &Runtime,
+) -> Result<Node, Error> {
+    let fault = fault.clone();
+    let executor = runtime.executor();
+    Node::start_wrapped(config, move |ring| Faulty {
+        ring,
+        fault,
+        executor,
+    })
+}
+
+/// An address nothing listens on, the port is free again once the listener is dropped
+fn free_addr() -> Result<SocketAddr, Error> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    Ok(listener.local_addr()?)
+}
+
+/// How a node fails on purpose
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fault {
+    /// Confirms the messages passed around the ring this much later than it takes them,
+    /// other calls are answered as usual
+    Slow(Duration),
+    /// Takes no calls and answers none, as if its process hung
+    Hung,
+}
+
+/// A node's service failing the way the cluster set it to, `WatchEvents` keeps working
+struct Faulty {
+    ring: Box<dyn Ring + Send + Sync>,
+    fault: Arc<Mutex<Option<Fault>>>,
+    executor: TaskExecutor,
+}
+
+impl Faulty {
+    fn answer<T, F>(&self, call: F) -> SingleResponse<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> SingleResponse<T>,
+    {
+        match *self.fault.lock().unwrap() {
+            Some(Fault::Hung) => SingleResponse::no_metadata(future::empty()),
+            _ => call(),
+        }
+    }
+
+    /// Answers a message passed around the ring, late if the node is slow
+    fn confirm<T, F>(&self, call: F) -> SingleResponse<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> SingleResponse<T>,
+    {
+        let delay = match *self.fault.lock().unwrap() {
+            Some(Fault::Slow(delay)) => delay,
+            _ => return self.answer(call),
+        };
+        let response = call().drop_metadata();
+        let (tx, rx) = oneshot::channel();
+        let timer = Delay::new(Instant::now() + delay).then(move |_| tx.send(()));
+        self.executor.spawn(timer.then(|_| Ok(())));
+        SingleResponse::no_metadata(rx.then(move |_| response))
+    }
+}
+
+impl Ring for Faulty {
+    fn start_roll_call(&self, o: RequestOptions, call: RollCall) -> SingleResponse<RollCall> {
+        self.confirm(|| self.ring.start_roll_call(o, call))
+    }
+
+    fn mark_itself(&self, o: RequestOptions, call: RollCall) -> SingleResponse<Empty> {
+        self.confirm(|| self.ring.mark_itself(o, call))
+    }
+
+    fn await_tally(&self, o: RequestOptions, call: RollCall) -> SingleResponse<Tally> {
+        self.answer(|| self.ring.await_tally(o, call))
+    }
+
+    fn join(&self, o: RequestOptions, request: JoinRequest) -> SingleResponse<JoinReply> {
+        self.answer(|| self.ring.join(o, request))
+    }
+
+    fn leave(&self, o: RequestOptions, request: LeaveRequest) -> SingleResponse<Empty> {
+        self.confirm(|| self.ring.leave(o, request))
+    }
+
+    fn heartbeat(&self, o: RequestOptions, empty: Empty) -> SingleResponse<Successors> {
+        self.answer(|| self.ring.heartbeat(o, empty))
+    }
+
+    fn elect(&self, o: RequestOptions, candidate: Candidate) -> SingleResponse<Empty> {
+        self.confirm(|| self.ring.elect(o, candidate))
+    }
+
+    fn elected(&self, o: RequestOptions, leader: Candidate) -> SingleResponse<Empty> {
+        self.confirm(|| self.ring.elected(o, leader))
+    }
+
+    fn get_leader(&self, o: RequestOptions, empty: Empty) -> SingleResponse<Candidate> {
+        self.answer(|| self.ring.get_leader(o, empty))
+    }
+
+    fn status(&self, o: RequestOptions, empty: Empty) -> SingleResponse<NodeStatus> {
+        self.answer(|| self.ring.status(o, empty))
+    }
+
+    fn watch_events(&self, o: RequestOptions, empty: Empty) -> StreamingResponse<Event> {
+        self.ring.watch_events(o, empty)
+    }
+
+    fn pass_token(&self, o: RequestOptions, token: Token) -> SingleResponse<Empty> {
+        self.confirm(|| self.ring.pass_token(o, token))
+    }
+
+    fn probe_token(&self, o: RequestOptions, probe: TokenProbe) -> SingleResponse<Empty> {
+        self.confirm(|| self.ring.probe_token(o, probe))
+    }
+
+    fn request_token(&self, o: RequestOptions, request: TokenRequest) -> SingleResponse<Empty> {
+        self.confirm(|| self.ring.request_token(o, request))
+    }
+
+    fn acquire(&self, o: RequestOptions, empty: Empty) -> SingleResponse<Lock> {
+        self.answer(|| self.ring.acquire(o, empty))
+    }
+
+    fn release(&self, o: RequestOptions, lock: Lock) -> SingleResponse<Empty> {
+        self.answer(|| self.ring.release(o, lock))
+    }
+}
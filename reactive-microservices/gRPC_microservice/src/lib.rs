@@ -1,37 +1,488 @@
+pub mod cluster;
+pub mod node;
 pub mod ring;
-mod ring_grpc;
+pub mod ring_grpc;
+mod tally;
+pub mod tls;
 
-use crate::ring::Empty;
+use crate::ring::{
+    Candidate, Empty, Event, JoinRequest, LeaveRequest, Lock, NodeStatus, RollCall, Tally, Token,
+    TokenProbe, TokenRequest,
+};
 use crate::ring_grpc::{Ring, RingClient};
+use crate::tls::ClientTls;
 
-use grpc::{ClientConf, ClientStubExt, Error as GrpcError, RequestOptions};
+use bytes::Bytes;
+use failure::Fail;
+use futures::future::{self, Either, Loop};
+use futures::{Future, Stream};
+use grpc::{
+    Client, ClientConf, ClientStub, ClientStubExt, Error as GrpcError, MetadataKey, RequestOptions,
+    SingleResponse,
+};
+use httpbis::ClientTlsOption;
+use rand::Rng;
+use std::cmp;
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::timer::{timeout, Delay, Timeout};
 
+/// gRPC status codes worth telling apart, see `grpc-status` in the gRPC HTTP/2 protocol
+const DEADLINE_EXCEEDED: i32 = 4;
+const UNAVAILABLE: i32 = 14;
+
+/// The result of a remote call, driven by a tokio runtime which also provides its timers
+pub type RemoteFuture<T> = Box<dyn Future<Item = T, Error = RemoteError> + Send>;
+pub type RemoteStream<T> = Box<dyn Stream<Item = T, Error = RemoteError> + Send>;
+
+#[derive(Debug, Fail)]
+pub enum RemoteError {
+    /// The node can't be reached, the call never got there
+    #[fail(display = "node unavailable: {}", _0)]
+    Unavailable(String),
+    /// No answer before the deadline, the call may or may not have been handled
+    #[fail(display = "no answer within {:?}", _0)]
+    Timeout(Duration),
+    /// The node answered with an error, trying again won't help
+    #[fail(display = "call rejected: {}", _0)]
+    Rejected(String),
+}
+
+/// Whether a call has the same effect when made twice, a call that timed out may have been
+/// handled and is only made again if it does
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Idempotence {
+    Idempotent,
+    NotIdempotent,
+}
+
+impl RemoteError {
+    pub fn is_retryable(&self) -> bool {
+        match *self {
+            RemoteError::Unavailable(_) | RemoteError::Timeout(_) => true,
+            RemoteError::Rejected(_) => false,
+        }
+    }
+
+    fn is_retryable_for(&self, idempotence: Idempotence) -> bool {
+        match *self {
+            RemoteError::Timeout(_) => idempotence == Idempotence::Idempotent,
+            _ => self.is_retryable(),
+        }
+    }
+
+    fn from_grpc(err: GrpcError, deadline: Duration) -> Self {
+        match err {
+            GrpcError::Io(_) | GrpcError::Http(_) => RemoteError::Unavailable(err.to_string()),
+            GrpcError::GrpcMessage(ref message) if message.grpc_status == UNAVAILABLE => {
+                RemoteError::Unavailable(err.to_string())
+            }
+            GrpcError::GrpcMessage(ref message) if message.grpc_status == DEADLINE_EXCEEDED => {
+                RemoteError::Timeout(deadline)
+            }
+            _ => RemoteError::Rejected(err.to_string()),
+        }
+    }
+
+    fn from_timeout(err: timeout::Error<GrpcError>, deadline: Duration) -> Self {
+        if err.is_elapsed() {
+            RemoteError::Timeout(deadline)
+        } else if err.is_timer() {
+            RemoteError::Unavailable("no timer to enforce the deadline".to_owned())
+        } else {
+            let err = err.into_inner().expect("neither elapsed nor a timer error");
+            RemoteError::from_grpc(err, deadline)
+        }
+    }
+}
+
+/// How long a call may take and how it's retried. Unavailable nodes are retried after a random
+/// delay below `backoff * 2^attempt`, capped at `max_backoff`, and so are timeouts of calls
+/// that are safe to make twice
+#[derive(Clone, Debug)]
+pub struct CallOptions {
+    pub deadline: Duration,
+    pub retries: u32,
+    pub backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for CallOptions {
+    fn default() -> Self {
+        Self {
+            deadline: Duration::from_secs(2),
+            retries: 3,
+            backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+impl CallOptions {
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt).unwrap_or(u32::max_value());
+        let ceiling = self
+            .backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff));
+        let millis = ceiling.as_secs() * 1000 + u64::from(ceiling.subsec_millis());
+        Duration::from_millis(rand::thread_rng().gen_range(0, cmp::max(millis, 1)))
+    }
+}
+
+/// Sends the deadline along as `grpc-timeout`, so that the server can tell when the caller
+/// gave up
+fn request_options(deadline: Duration) -> RequestOptions {
+    let mut options = RequestOptions::new();
+    let timeout = Bytes::from(grpc_timeout(deadline));
+    options
+        .metadata
+        .add(MetadataKey::from("grpc-timeout"), timeout);
+    options
+}
+
+/// In milliseconds, or in seconds where that takes more than the 8 digits allowed
+fn grpc_timeout(deadline: Duration) -> String {
+    let millis = deadline.as_millis();
+    if millis < 100_000_000 {
+        format!("{}m", millis)
+    } else {
+        format!("{}S", cmp::min(deadline.as_secs(), 99_999_999))
+    }
+}
+
+#[derive(Clone)]
 pub struct Remote {
-    client: RingClient,
+    client: Arc<RingClient>,
+    options: CallOptions,
 }
 
 impl Remote {
     pub fn new(addr: SocketAddr) -> Result<Self, GrpcError> {
+        Self::with_options(addr, CallOptions::default())
+    }
+
+    pub fn with_options(addr: SocketAddr, options: CallOptions) -> Result<Self, GrpcError> {
         let host = addr.ip().to_string();
         let port = addr.port();
         let conf = ClientConf::default();
-        let client = RingClient::new_plain(&host, port, conf)?;
+        let client = Arc::new(RingClient::new_plain(&host, port, conf)?);
+
+        Ok(Self { client, options })
+    }
+
+    /// Over TLS if `tls` is given, plain otherwise
+    pub fn connect(addr: SocketAddr, tls: Option<&ClientTls>) -> Result<Self, GrpcError> {
+        let tls = match tls {
+            Some(tls) => tls,
+            None => return Self::new(addr),
+        };
+        let domain = tls.domain.clone().unwrap_or_else(|| addr.ip().to_string());
+        let option = ClientTlsOption::Tls(domain.clone(), tls.connector.clone());
+        let client = Client::new_expl(&addr, &domain, option, ClientConf::default())?;
+        let client = Arc::new(RingClient::with_client(Arc::new(client)));
+
+        Ok(Self {
+            client,
+            options: CallOptions::default(),
+        })
+    }
+
+    /// The same connection with another deadline, for calls expected to take longer
+    pub fn with_deadline(&self, deadline: Duration) -> Self {
+        let mut options = self.options.clone();
+        options.deadline = deadline;
+        Self {
+            client: self.client.clone(),
+            options,
+        }
+    }
+
+    /// Makes the call, again after a backoff for as long as it fails in a retryable way
+    fn call<T, F>(&self, idempotence: Idempotence, call: F) -> RemoteFuture<T>
+    where
+        T: Send + 'static,
+        F: Fn(&RingClient, RequestOptions) -> SingleResponse<T> + Send + 'static,
+    {
+        let client = self.client.clone();
+        let options = self.options.clone();
+        let attempts = future::loop_fn(0, move |attempt| {
+            let options = options.clone();
+            let deadline = options.deadline;
+            let response = call(&client, request_options(deadline)).drop_metadata();
+            Timeout::new(response, deadline).then(move |result| match result {
+                Ok(value) => Either::A(future::ok(Loop::Break(value))),
+                Err(err) => {
+                    let err = RemoteError::from_timeout(err, deadline);
+                    if !err.is_retryable_for(idempotence) || attempt >= options.retries {
+                        return Either::A(future::err(err));
+                    }
+                    let delay = Delay::new(Instant::now() + options.backoff(attempt));
+                    Either::B(delay.then(move |_| Ok(Loop::Continue(attempt + 1))))
+                }
+            })
+        });
+        Box::new(attempts)
+    }
+
+    /// Passes `call` on, a call without id starts a new roll call on the remote node, which
+    /// answers with the id it picked
+    pub fn start_roll_call(&self, call: RollCall) -> RemoteFuture<RollCall> {
+        self.call(Idempotence::NotIdempotent, move |client, options| {
+            client.start_roll_call(options, call.clone())
+        })
+    }
+
+    pub fn mark_itself(&self, call: RollCall) -> RemoteFuture<Empty> {
+        self.call(Idempotence::NotIdempotent, move |client, options| {
+            client.mark_itself(options, call.clone())
+        })
+    }
+
+    /// Waits for the tally of a roll call the remote node initiated
+    pub fn await_tally(&self, id: &str) -> RemoteFuture<Tally> {
+        let mut call = RollCall::new();
+        call.set_id(id.to_owned());
+        self.call(Idempotence::Idempotent, move |client, options| {
+            client.await_tally(options, call.clone())
+        })
+    }
+
+    /// Splices `address` in after the remote node, returns the successor `address` should take
+    pub fn join(&self, address: SocketAddr) -> RemoteFuture<SocketAddr> {
+        let mut request = JoinRequest::new();
+        request.set_address(address.to_string());
+        let reply = self.call(Idempotence::NotIdempotent, move |client, options| {
+            client.join(options, request.clone())
+        });
+        Box::new(reply.and_then(|reply| {
+            reply
+                .get_successor()
+                .parse()
+                .map_err(|_| RemoteError::Rejected("invalid successor address".to_owned()))
+        }))
+    }
+
+    /// Tells the ring that `address` leaves and `successor` takes its place, without a
+    /// successor it asks the remote node itself to leave
+    pub fn leave(&self, address: SocketAddr, successor: Option<SocketAddr>) -> RemoteFuture<Empty> {
+        let mut request = LeaveRequest::new();
+        request.set_address(address.to_string());
+        if let Some(successor) = successor {
+            request.set_successor(successor.to_string());
+        }
+        self.call(Idempotence::NotIdempotent, move |client, options| {
+            client.leave(options, request.clone())
+        })
+    }
+
+    /// Passes on the notice that `address` left and `successor` takes its place, `origin` is
+    /// the first node that passed it on
+    pub fn left(
+        &self,
+        address: SocketAddr,
+        successor: SocketAddr,
+        origin: SocketAddr,
+    ) -> RemoteFuture<Empty> {
+        let mut request = LeaveRequest::new();
+        request.set_address(address.to_string());
+        request.set_successor(successor.to_string());
+        request.set_origin(origin.to_string());
+        self.call(Idempotence::NotIdempotent, move |client, options| {
+            client.leave(options, request.clone())
+        })
+    }
+
+    /// Checks the remote node is alive, it answers with its successor list
+    pub fn heartbeat(&self) -> RemoteFuture<Vec<SocketAddr>> {
+        let reply = self.call(Idempotence::Idempotent, |client, options| {
+            client.heartbeat(options, Empty::new())
+        });
+        Box::new(reply.and_then(|reply| {
+            reply
+                .get_addresses()
+                .iter()
+                .map(|address| {
+                    address
+                        .parse()
+                        .map_err(|_| RemoteError::Rejected("invalid successor address".to_owned()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        }))
+    }
+
+    /// Passes `candidate` on, a candidate without address asks the remote node to start an
+    /// election
+    pub fn elect(&self, candidate: Candidate) -> RemoteFuture<Empty> {
+        self.call(Idempotence::NotIdempotent, move |client, options| {
+            client.elect(options, candidate.clone())
+        })
+    }
+
+    pub fn elected(&self, leader: Candidate) -> RemoteFuture<Empty> {
+        self.call(Idempotence::NotIdempotent, move |client, options| {
+            client.elected(options, leader.clone())
+        })
+    }
+
+    /// The leader the remote node knows of, if any
+    pub fn get_leader(&self) -> RemoteFuture<Option<Candidate>> {
+        let leader = self.call(Idempotence::Idempotent, |client, options| {
+            client.get_leader(options, Empty::new())
+        });
+        Box::new(leader.map(|leader| {
+            if leader.get_address().is_empty() {
+                None
+            } else {
+                Some(leader)
+            }
+        }))
+    }
+
+    pub fn pass_token(&self, token: Token) -> RemoteFuture<Empty> {
+        self.call(Idempotence::Idempotent, move |client, options| {
+            client.pass_token(options, token.clone())
+        })
+    }
+
+    pub fn probe_token(&self, probe: TokenProbe) -> RemoteFuture<Empty> {
+        self.call(Idempotence::NotIdempotent, move |client, options| {
+            client.probe_token(options, probe.clone())
+        })
+    }
+
+    /// Asks for the token on behalf of `requester`
+    pub fn request_token(&self, requester: SocketAddr) -> RemoteFuture<Empty> {
+        let mut request = TokenRequest::new();
+        request.set_requester(requester.to_string());
+        self.call(Idempotence::Idempotent, move |client, options| {
+            client.request_token(options, request.clone())
+        })
+    }
+
+    /// Waits for the remote node to grant a lock. Never retried, a lock granted to an earlier
+    /// attempt would block everyone else until its lease runs out
+    pub fn acquire(&self) -> RemoteFuture<Lock> {
+        let mut remote = self.clone();
+        remote.options.retries = 0;
+        remote.call(Idempotence::NotIdempotent, |client, options| {
+            client.acquire(options, Empty::new())
+        })
+    }
+
+    pub fn release(&self, lock: Lock) -> RemoteFuture<Empty> {
+        self.call(Idempotence::NotIdempotent, move |client, options| {
+            client.release(options, lock.clone())
+        })
+    }
+
+    pub fn status(&self) -> RemoteFuture<NodeStatus> {
+        self.call(Idempotence::Idempotent, |client, options| {
+            client.status(options, Empty::new())
+        })
+    }
+
+    /// The remote node's state transitions as they happen, the stream has no deadline and
+    /// isn't retried
+    pub fn watch_events(&self) -> RemoteStream<Event> {
+        let deadline = self.options.deadline;
+        let events = self
+            .client
+            .watch_events(RequestOptions::new(), Empty::new())
+            .drop_metadata();
+        Box::new(events.map_err(move |err| RemoteError::from_grpc(err, deadline)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grpc::GrpcMessageError;
+    use std::io;
+
+    const DEADLINE: Duration = Duration::from_secs(2);
+
+    fn grpc_status(status: i32) -> GrpcError {
+        GrpcError::GrpcMessage(GrpcMessageError {
+            grpc_status: status,
+            grpc_message: "status".to_owned(),
+        })
+    }
+
+    #[test]
+    fn backoff_stays_below_its_ceiling() {
+        let options = CallOptions::default();
+        for attempt in 0..5 {
+            let ceiling = options.backoff * 2u32.pow(attempt);
+            for _ in 0..100 {
+                let backoff = options.backoff(attempt);
+                assert!(backoff <= options.max_backoff);
+                assert!(backoff < ceiling, "{:?} for attempt {}", backoff, attempt);
+            }
+        }
+    }
+
+    #[test]
+    fn backoff_survives_huge_attempts() {
+        let options = CallOptions::default();
+        for &attempt in &[31, 32, 33, 64, u32::MAX] {
+            assert!(options.backoff(attempt) <= options.max_backoff);
+        }
+        let options = CallOptions {
+            backoff: Duration::from_secs(u64::MAX),
+            ..CallOptions::default()
+        };
+        assert!(options.backoff(1) <= options.max_backoff);
+    }
 
-        Ok(Self { client })
+    #[test]
+    fn grpc_errors_map_to_remote_errors() {
+        let refused = io::Error::new(io::ErrorKind::ConnectionRefused, "refused");
+        match RemoteError::from_grpc(GrpcError::Io(refused), DEADLINE) {
+            RemoteError::Unavailable(_) => {}
+            err => panic!("I/O error mapped to {:?}", err),
+        }
+        match RemoteError::from_grpc(grpc_status(UNAVAILABLE), DEADLINE) {
+            RemoteError::Unavailable(_) => {}
+            err => panic!("UNAVAILABLE mapped to {:?}", err),
+        }
+        match RemoteError::from_grpc(grpc_status(DEADLINE_EXCEEDED), DEADLINE) {
+            RemoteError::Timeout(deadline) => assert_eq!(deadline, DEADLINE),
+            err => panic!("DEADLINE_EXCEEDED mapped to {:?}", err),
+        }
+        // NOT_FOUND
+        match RemoteError::from_grpc(grpc_status(5), DEADLINE) {
+            RemoteError::Rejected(_) => {}
+            err => panic!("NOT_FOUND mapped to {:?}", err),
+        }
+        match RemoteError::from_grpc(GrpcError::Other("lock not held"), DEADLINE) {
+            RemoteError::Rejected(_) => {}
+            err => panic!("an error answer mapped to {:?}", err),
+        }
+        match RemoteError::from_timeout(timeout::Error::elapsed(), DEADLINE) {
+            RemoteError::Timeout(deadline) => assert_eq!(deadline, DEADLINE),
+            err => panic!("an elapsed deadline mapped to {:?}", err),
+        }
     }
 
-    pub fn start_roll_call(&self) -> Result<Empty, GrpcError> {
-        self.client
-            .start_roll_call(RequestOptions::new(), Empty::new())
-            .wait()
-            .map(|(_, value, _)| value)
+    #[test]
+    fn timeouts_are_retried_for_idempotent_calls_only() {
+        let timeout = RemoteError::Timeout(DEADLINE);
+        assert!(timeout.is_retryable_for(Idempotence::Idempotent));
+        assert!(!timeout.is_retryable_for(Idempotence::NotIdempotent));
+        let unavailable = RemoteError::Unavailable("refused".to_owned());
+        assert!(unavailable.is_retryable_for(Idempotence::NotIdempotent));
+        let rejected = RemoteError::Rejected("invalid".to_owned());
+        assert!(!rejected.is_retryable_for(Idempotence::Idempotent));
     }
 
-    pub fn mark_itself(&self) -> Result<Empty, GrpcError> {
-        self.client
-            .mark_itself(RequestOptions::new(), Empty::new())
-            .wait()
-            .map(|(_, value, _)| value)
+    #[test]
+    fn grpc_timeout_fits_eight_digits() {
+        assert_eq!(grpc_timeout(Duration::from_millis(1500)), "1500m");
+        assert_eq!(grpc_timeout(Duration::from_secs(99_999)), "99999000m");
+        assert_eq!(grpc_timeout(Duration::from_secs(100_000)), "100000S");
+        let forever = Duration::from_secs(u64::MAX);
+        assert_eq!(grpc_timeout(forever), "99999999S");
     }
 }
@@ -0,0 +1,1446 @@
+use crate::ring::{
+    Candidate, Counter, Empty, Event, JoinReply, JoinRequest, LeaveRequest, Lock, NodeStatus,
+    RollCall, Successors, Tally, Token, TokenProbe, TokenRequest,
+};
+use crate::ring_grpc::{Ring, RingServer};
+use crate::tally::Tallies;
+use crate::tls::{ClientTls, TlsConfig};
+use crate::{Remote, RemoteError, RemoteFuture};
+use failure::Error;
+use futures::sync::mpsc::{self, Sender as EventSender};
+use futures::sync::oneshot;
+use futures::{Future, Stream};
+use grpc::{
+    Error as GrpcError, RequestOptions, Server, ServerBuilder, SingleResponse, StreamingResponse,
+};
+use log::{debug, info, trace, warn};
+use std::cmp;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tls_api::TlsAcceptor;
+use tokio::runtime::Runtime;
+use tokio::timer::Delay;
+
+/// How many successors a node keeps to route around failed ones
+pub const SUCCESSORS: usize = 3;
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+/// How long a successor dropped as down still gets heartbeats, so that it's taken back if it
+/// only failed for a moment
+const RECHECK_DROPPED: Duration = Duration::from_secs(60);
+/// A successor missing this many heartbeats in a row is considered down, a message it fails
+/// to take is enough on its own
+const MAX_MISSED_HEARTBEATS: u32 = 3;
+/// How long a client holds a lock at most, it's released on its own after that
+const LOCK_LEASE: Duration = Duration::from_secs(10);
+/// Events a `WatchEvents` stream may fall behind by before it's dropped
+const WATCH_BUFFER: usize = 64;
+/// The leader looks for the token once it hasn't seen it for this long
+const TOKEN_TIMEOUT: Duration = Duration::from_secs(3);
+
+macro_rules! try_or_response {
+    ($x:expr) => {{
+        match $x {
+            Ok(value) => value,
+            Err(err) => {
+                let error = GrpcError::Panic(err.to_string());
+                return SingleResponse::err(error);
+            }
+        }
+    }};
+}
+
+enum Action {
+    StartRollCall(RollCall),
+    MarkItself(RollCall),
+    /// `node` becomes the successor, the old one is sent back for `node` to take over
+    Join {
+        node: SocketAddr,
+        reply: oneshot::Sender<SocketAddr>,
+    },
+    /// This node leaves the ring, the reply tells whether it did, it stays while a lock granted
+    /// here is held
+    Leave {
+        reply: oneshot::Sender<bool>,
+    },
+    /// `node` left the ring and `successor` takes its place, `origin` is the first node that
+    /// passed the notice on, none if it comes from `node` itself
+    Left {
+        node: SocketAddr,
+        successor: SocketAddr,
+        origin: Option<SocketAddr>,
+    },
+    /// Time to check the successor is alive
+    Heartbeat,
+    /// The successor answered a heartbeat, or didn't
+    HeartbeatReply {
+        from: SocketAddr,
+        result: Result<Vec<SocketAddr>, RemoteError>,
+    },
+    /// A successor dropped as down answered a heartbeat, or didn't
+    RecheckReply {
+        node: SocketAddr,
+        result: Result<Vec<SocketAddr>, RemoteError>,
+    },
+    StartElection,
+    Elect(Candidate),
+    Elected(Candidate),
+    PassToken(Token),
+    ProbeToken(TokenProbe),
+    /// `requester` asks for the token
+    RequestToken(SocketAddr),
+    /// The lease of the lock `id` ran out
+    LeaseExpired(String),
+    /// A client waits for `lock` to be granted
+    Acquire {
+        lock: Lock,
+        reply: oneshot::Sender<Lock>,
+    },
+    /// A client gives `lock` back, the reply tells whether it held it
+    Release {
+        lock: Lock,
+        reply: oneshot::Sender<bool>,
+    },
+    /// The node is killed, the worker stops without telling the ring
+    Stop,
+    /// The message at the head of the outbox was delivered to `to`, or failed for good
+    Sent {
+        to: SocketAddr,
+        error: Option<RemoteError>,
+    },
+}
+
+impl Action {
+    fn name(&self) -> &'static str {
+        match *self {
+            Action::StartRollCall(_) => "StartRollCall",
+            Action::MarkItself(_) => "MarkItself",
+            Action::Join { .. } => "Join",
+            Action::Leave { .. } => "Leave",
+            Action::Left { .. } => "Left",
+            Action::Heartbeat => "Heartbeat",
+            Action::HeartbeatReply { .. } => "HeartbeatReply",
+            Action::RecheckReply { .. } => "RecheckReply",
+            Action::StartElection => "StartElection",
+            Action::Elect(_) => "Elect",
+            Action::Elected(_) => "Elected",
+            Action::PassToken(_) => "PassToken",
+            Action::ProbeToken(_) => "ProbeToken",
+            Action::RequestToken(_) => "RequestToken",
+            Action::LeaseExpired(_) => "LeaseExpired",
+            Action::Acquire { .. } => "Acquire",
+            Action::Release { .. } => "Release",
+            Action::Stop => "Stop",
+            Action::Sent { .. } => "Sent",
+        }
+    }
+}
+
+/// A message for the successor, kept until it's delivered so that it can be rerouted
+#[derive(Clone)]
+enum Message {
+    StartRollCall(RollCall),
+    MarkItself(RollCall),
+    Left {
+        node: SocketAddr,
+        successor: SocketAddr,
+        origin: Option<SocketAddr>,
+    },
+    Elect(Candidate),
+    Elected(Candidate),
+    PassToken(Token),
+    ProbeToken(TokenProbe),
+    RequestToken(SocketAddr),
+}
+
+impl Message {
+    fn send(self, remote: &Remote) -> RemoteFuture<()> {
+        match self {
+            Message::StartRollCall(call) => Box::new(remote.start_roll_call(call).map(|_| ())),
+            Message::MarkItself(call) => Box::new(remote.mark_itself(call).map(|_| ())),
+            Message::Left {
+                node,
+                successor,
+                origin: None,
+            } => Box::new(remote.leave(node, Some(successor)).map(|_| ())),
+            Message::Left {
+                node,
+                successor,
+                origin: Some(origin),
+            } => Box::new(remote.left(node, successor, origin).map(|_| ())),
+            Message::Elect(candidate) => Box::new(remote.elect(candidate).map(|_| ())),
+            Message::Elected(leader) => Box::new(remote.elected(leader).map(|_| ())),
+            Message::PassToken(token) => Box::new(remote.pass_token(token).map(|_| ())),
+            Message::ProbeToken(probe) => Box::new(remote.probe_token(probe).map(|_| ())),
+            Message::RequestToken(requester) => {
+                Box::new(remote.request_token(requester).map(|_| ()))
+            }
+        }
+    }
+}
+
+/// What the handlers answer without asking the worker, which may be busy with a remote call
+#[derive(Default)]
+struct NodeState {
+    /// Nearest first, never empty once the worker runs
+    successors: RwLock<Vec<SocketAddr>>,
+    leader: RwLock<Option<Candidate>>,
+    tallies: Tallies,
+    /// The ids of the roll calls the node is in
+    roll_calls: Mutex<HashSet<String>>,
+    in_election: AtomicBool,
+    /// How many actions of each kind the worker handled
+    handled: Mutex<BTreeMap<&'static str, u64>>,
+    /// The most recent error and when it happened, the node may have recovered since
+    last_error: Mutex<Option<(String, SystemTime)>>,
+    /// `WatchEvents` streams, dropped once their client is gone or falls too far behind
+    watchers: Mutex<Vec<EventSender<Event>>>,
+    /// Set when the worker exits, `WatchEvents` streams end then
+    stopped: AtomicBool,
+}
+
+impl NodeState {
+    fn publish(&self, event: Event) {
+        let mut watchers = self.watchers.lock().unwrap();
+        // A full stream is one whose client doesn't keep up
+        let kept = watchers
+            .drain(..)
+            .filter_map(|mut watcher| match watcher.try_send(event.clone()) {
+                Ok(()) => Some(watcher),
+                Err(_) => None,
+            })
+            .collect();
+        *watchers = kept;
+    }
+
+    /// Ends the `WatchEvents` streams
+    fn stop_watchers(&self) {
+        let mut watchers = self.watchers.lock().unwrap();
+        self.stopped.store(true, Ordering::SeqCst);
+        watchers.clear();
+    }
+}
+
+struct RingImpl {
+    addr: SocketAddr,
+    sender: Mutex<Sender<Action>>,
+    state: Arc<NodeState>,
+    roll_calls: AtomicUsize,
+    locks: AtomicUsize,
+}
+
+impl RingImpl {
+    fn new(addr: SocketAddr, sender: Sender<Action>, state: Arc<NodeState>) -> Self {
+        Self {
+            addr,
+            sender: Mutex::new(sender),
+            state,
+            roll_calls: AtomicUsize::new(0),
+            locks: AtomicUsize::new(0),
+        }
+    }
+
+    fn enqueue(&self, action: Action) -> Result<(), String> {
+        let tx = self.sender.lock().map_err(|err| err.to_string())?;
+        tx.send(action).map_err(|err| err.to_string())
+    }
+
+    fn send_action(&self, action: Action) -> SingleResponse<Empty> {
+        try_or_response!(self.enqueue(action));
+        let result = Empty::new();
+        SingleResponse::completed(result)
+    }
+}
+
+impl Ring for RingImpl {
+    fn start_roll_call(&self, _: RequestOptions, mut call: RollCall) -> SingleResponse<RollCall> {
+        trace!("START_ROLL_CALL");
+        if call.get_id().is_empty() {
+            let number = self.roll_calls.fetch_add(1, Ordering::SeqCst);
+            call.set_id(format!("{}-{}", self.addr, number));
+            call.set_initiator(self.addr.to_string());
+            self.state.tallies.start(call.get_id().to_owned());
+        }
+        try_or_response!(self.enqueue(Action::StartRollCall(call.clone())));
+        SingleResponse::completed(call)
+    }
+
+    fn mark_itself(&self, _: RequestOptions, call: RollCall) -> SingleResponse<Empty> {
+        trace!("MARK_ITSELF");
+        self.send_action(Action::MarkItself(call))
+    }
+
+    fn await_tally(&self, _: RequestOptions, call: RollCall) -> SingleResponse<Tally> {
+        trace!("AWAIT_TALLY");
+        self.state.tallies.wait(call.get_id())
+    }
+
+    fn join(&self, _: RequestOptions, request: JoinRequest) -> SingleResponse<JoinReply> {
+        trace!("JOIN");
+        let node = try_or_response!(request.get_address().parse::<SocketAddr>());
+        let (tx, rx) = oneshot::channel();
+        try_or_response!(self.enqueue(Action::Join { node, reply: tx }));
+        let reply = rx
+            .map_err(|_| GrpcError::Other("node stopped"))
+            .map(|successor| {
+                let mut result = JoinReply::new();
+                result.set_successor(successor.to_string());
+                result
+            });
+        SingleResponse::no_metadata(reply)
+    }
+
+    fn leave(&self, _: RequestOptions, request: LeaveRequest) -> SingleResponse<Empty> {
+        trace!("LEAVE");
+        if request.get_successor().is_empty() {
+            let (tx, rx) = oneshot::channel();
+            try_or_response!(self.enqueue(Action::Leave { reply: tx }));
+            let left = rx
+                .map_err(|_| GrpcError::Other("node stopped"))
+                .and_then(|left| {
+                    if left {
+                        Ok(Empty::new())
+                    } else {
+                        Err(GrpcError::Other("a lock is held"))
+                    }
+                });
+            return SingleResponse::no_metadata(left);
+        }
+        let node = try_or_response!(request.get_address().parse::<SocketAddr>());
+        let successor = try_or_response!(request.get_successor().parse::<SocketAddr>());
+        let origin = match request.get_origin() {
+            "" => None,
+            origin => Some(try_or_response!(origin.parse::<SocketAddr>())),
+        };
+        self.send_action(Action::Left {
+            node,
+            successor,
+            origin,
+        })
+    }
+
+    fn heartbeat(&self, _: RequestOptions, _: Empty) -> SingleResponse<Successors> {
+        trace!("HEARTBEAT");
+        let successors = try_or_response!(self.state.successors.read());
+        let mut result = Successors::new();
+        for successor in successors.iter() {
+            result.mut_addresses().push(successor.to_string());
+        }
+        SingleResponse::completed(result)
+    }
+
+    fn elect(&self, _: RequestOptions, candidate: Candidate) -> SingleResponse<Empty> {
+        trace!("ELECT");
+        if candidate.get_address().is_empty() {
+            return self.send_action(Action::StartElection);
+        }
+        self.send_action(Action::Elect(candidate))
+    }
+
+    fn elected(&self, _: RequestOptions, leader: Candidate) -> SingleResponse<Empty> {
+        trace!("ELECTED");
+        self.send_action(Action::Elected(leader))
+    }
+
+    fn get_leader(&self, _: RequestOptions, _: Empty) -> SingleResponse<Candidate> {
+        trace!("GET_LEADER");
+        let leader = try_or_response!(self.state.leader.read());
+        SingleResponse::completed(leader.clone().unwrap_or_default())
+    }
+
+    fn pass_token(&self, _: RequestOptions, token: Token) -> SingleResponse<Empty> {
+        trace!("PASS_TOKEN");
+        self.send_action(Action::PassToken(token))
+    }
+
+    fn probe_token(&self, _: RequestOptions, probe: TokenProbe) -> SingleResponse<Empty> {
+        trace!("PROBE_TOKEN");
+        self.send_action(Action::ProbeToken(probe))
+    }
+
+    fn request_token(&self, _: RequestOptions, request: TokenRequest) -> SingleResponse<Empty> {
+        trace!("REQUEST_TOKEN");
+        let requester = try_or_response!(request.get_requester().parse::<SocketAddr>());
+        self.send_action(Action::RequestToken(requester))
+    }
+
+    fn acquire(&self, _: RequestOptions, _: Empty) -> SingleResponse<Lock> {
+        trace!("ACQUIRE");
+        let number = self.locks.fetch_add(1, Ordering::SeqCst);
+        let mut lock = Lock::new();
+        lock.set_id(format!("{}-lock-{}", self.addr, number));
+        let (tx, rx) = oneshot::channel();
+        try_or_response!(self.enqueue(Action::Acquire { lock, reply: tx }));
+        SingleResponse::no_metadata(rx.map_err(|_| GrpcError::Other("node stopped")))
+    }
+
+    fn release(&self, _: RequestOptions, lock: Lock) -> SingleResponse<Empty> {
+        trace!("RELEASE");
+        let (tx, rx) = oneshot::channel();
+        try_or_response!(self.enqueue(Action::Release { lock, reply: tx }));
+        let released = rx
+            .map_err(|_| GrpcError::Other("node stopped"))
+            .and_then(|held| {
+                if held {
+                    Ok(Empty::new())
+                } else {
+                    Err(GrpcError::Other("lock not held"))
+                }
+            });
+        SingleResponse::no_metadata(released)
+    }
+
+    fn status(&self, _: RequestOptions, _: Empty) -> SingleResponse<NodeStatus> {
+        trace!("STATUS");
+        let mut result = NodeStatus::new();
+        result.set_address(self.addr.to_string());
+        let roll_calls = try_or_response!(self.state.roll_calls.lock());
+        result.set_in_roll_call(!roll_calls.is_empty());
+        for id in roll_calls.iter() {
+            result.mut_roll_calls().push(id.clone());
+        }
+        result.set_in_election(self.state.in_election.load(Ordering::SeqCst));
+        let successors = try_or_response!(self.state.successors.read());
+        if let Some(successor) = successors.first() {
+            result.set_successor(successor.to_string());
+        }
+        let leader = try_or_response!(self.state.leader.read());
+        if let Some(ref leader) = *leader {
+            result.set_leader(leader.get_address().to_owned());
+        }
+        let handled = try_or_response!(self.state.handled.lock());
+        for (action, &count) in handled.iter() {
+            let mut counter = Counter::new();
+            counter.set_action(action.to_string());
+            counter.set_count(count);
+            result.mut_handled().push(counter);
+        }
+        let last_error = try_or_response!(self.state.last_error.lock());
+        if let Some((ref err, at)) = *last_error {
+            result.set_last_error(err.clone());
+            let since_epoch = at.duration_since(UNIX_EPOCH).unwrap_or_default();
+            result.set_last_error_at(since_epoch.as_secs());
+        }
+        SingleResponse::completed(result)
+    }
+
+    fn watch_events(&self, _: RequestOptions, _: Empty) -> StreamingResponse<Event> {
+        trace!("WATCH_EVENTS");
+        let (tx, rx) = mpsc::channel(WATCH_BUFFER);
+        match self.state.watchers.lock() {
+            Ok(_) if self.state.stopped.load(Ordering::SeqCst) => {
+                return StreamingResponse::completed(Vec::new());
+            }
+            Ok(mut watchers) => watchers.push(tx),
+            Err(err) => return StreamingResponse::err(GrpcError::Panic(err.to_string())),
+        }
+        StreamingResponse::no_metadata(rx.map_err(|()| GrpcError::Other("node stopped")))
+    }
+}
+
+/// How a node starts: `join` splices it in after a node of a running ring, `next` sets the
+/// successor directly and without either the node is a ring of its own
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub addr: SocketAddr,
+    /// Derived from the address if not set, so that nodes get distinct ids
+    pub id: Option<u64>,
+    pub join: Option<SocketAddr>,
+    pub next: Option<SocketAddr>,
+    /// Mutual TLS for the server and the node's calls, plain without
+    pub tls: Option<TlsConfig>,
+}
+
+impl Config {
+    pub fn new(addr: SocketAddr) -> Self {
+        Self {
+            addr,
+            id: None,
+            join: None,
+            next: None,
+            tls: None,
+        }
+    }
+}
+
+/// A running ring node: the gRPC server and the worker thread behind it
+pub struct Node {
+    addr: SocketAddr,
+    state: Arc<NodeState>,
+    sender: Sender<Action>,
+    worker: Option<JoinHandle<Result<(), Error>>>,
+    _server: Server,
+}
+
+impl Node {
+    pub fn start(config: Config) -> Result<Self, Error> {
+        Self::start_serving(config, |ring| ring)
+    }
+
+    /// Starts the node with its service wrapped by `wrap`, so that tests can get in between
+    /// the node and its peers
+    pub(crate) fn start_wrapped<R, F>(config: Config, wrap: F) -> Result<Self, Error>
+    where
+        R: Ring + Send + Sync + 'static,
+        F: FnOnce(Box<dyn Ring + Send + Sync>) -> R,
+    {
+        Self::start_serving(config, |ring| wrap(Box::new(ring)))
+    }
+
+    fn start_serving<R, F>(config: Config, wrap: F) -> Result<Self, Error>
+    where
+        R: Ring + Send + Sync + 'static,
+        F: FnOnce(RingImpl) -> R,
+    {
+        let (tx, rx) = channel();
+        let addr = config.addr;
+        let state = Arc::new(NodeState::default());
+        let ring = wrap(RingImpl::new(addr, tx.clone(), state.clone()));
+        let (server, tls) = match config.tls {
+            Some(ref tls) => {
+                let mut server = ServerBuilder::new();
+                server.http.set_tls(tls.acceptor()?);
+                (serve(server, addr, ring)?, Some(tls.client()?))
+            }
+            None => (serve(ServerBuilder::new_plain(), addr, ring)?, None),
+        };
+
+        let mut runtime = Runtime::new()?;
+        let id = config.id.unwrap_or_else(|| node_id(addr));
+        let next = initial_successor(&config, tls.as_ref(), &state, &mut runtime)?;
+        let heartbeats = tx.clone();
+        thread::spawn(move || heartbeat_loop(heartbeats));
+        let worker = Worker::new(addr, id, next, state.clone(), tx.clone(), runtime, tls)?;
+        let worker = thread::spawn(move || worker.run(rx));
+
+        Ok(Self {
+            addr,
+            state,
+            sender: tx,
+            worker: Some(worker),
+            _server: server,
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Whether the node is in any roll call
+    pub fn in_roll_call(&self) -> bool {
+        !self.state.roll_calls.lock().unwrap().is_empty()
+    }
+
+    /// Nearest first
+    pub fn successors(&self) -> Vec<SocketAddr> {
+        self.state.successors.read().unwrap().clone()
+    }
+
+    pub fn leader(&self) -> Option<Candidate> {
+        self.state.leader.read().unwrap().clone()
+    }
+
+    /// Runs until the node has left the ring
+    pub fn wait(mut self) -> Result<(), Error> {
+        match self.worker.take() {
+            Some(worker) => join(worker),
+            None => Ok(()),
+        }
+    }
+
+    /// Stops the node at once without telling the ring, as if it crashed
+    pub fn kill(mut self) -> Result<(), Error> {
+        self.stop()
+    }
+
+    fn stop(&mut self) -> Result<(), Error> {
+        match self.worker.take() {
+            Some(worker) => {
+                // The worker may have stopped already, leaving the ring
+                let _ = self.sender.send(Action::Stop);
+                join(worker)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        if let Err(err) = self.stop() {
+            warn!("{} stopped with an error: {}", self.addr, err);
+        }
+    }
+}
+
+fn serve<A: TlsAcceptor, R: Ring + Send + Sync + 'static>(
+    mut server: ServerBuilder<A>,
+    addr: SocketAddr,
+    ring: R,
+) -> Result<Server, Error> {
+    server.http.set_addr(addr)?;
+    server.add_service(RingServer::new_service_def(ring));
+    server.http.set_cpu_pool_threads(4);
+    Ok(server.build()?)
+}
+
+fn join(worker: JoinHandle<Result<(), Error>>) -> Result<(), Error> {
+    worker
+        .join()
+        .map_err(|_| failure::err_msg("worker panicked"))?
+}
+
+fn node_id(addr: SocketAddr) -> u64 {
+    let ip = match addr.ip() {
+        IpAddr::V4(ip) => u64::from(u32::from(ip)),
+        IpAddr::V6(ip) => {
+            let ip = u128::from(ip);
+            (ip >> 64) as u64 ^ ip as u64
+        }
+    };
+    ip << 16 | u64::from(addr.port())
+}
+
+fn initial_successor(
+    config: &Config,
+    tls: Option<&ClientTls>,
+    state: &NodeState,
+    runtime: &mut Runtime,
+) -> Result<SocketAddr, Error> {
+    if let Some(node) = config.join {
+        let remote = Remote::connect(node, tls)?;
+        let next = runtime.block_on(remote.join(config.addr))?;
+        debug!("JOINED AFTER {}, BEFORE {}", node, next);
+        *state.leader.write().unwrap() = runtime.block_on(remote.get_leader())?;
+        Ok(next)
+    } else {
+        Ok(config.next.unwrap_or(config.addr))
+    }
+}
+
+fn heartbeat_loop(sender: Sender<Action>) {
+    loop {
+        thread::sleep(HEARTBEAT_INTERVAL);
+        if sender.send(Action::Heartbeat).is_err() {
+            break;
+        }
+    }
+}
+
+/*
+    The worker keeps the first few successors of the node, nearest first, and refreshes them
+    from the successor's own list on every heartbeat. A successor that fails a heartbeat or a
+    message is dropped and the next one in the list takes its place, so a roll call routes
+    around a failed node instead of stopping there. A dropped successor still gets heartbeats
+    for a while, it's taken back if it answers and its own successor is the node that took
+    its place.
+
+    Messages for the successor queue up in the outbox and go out one at a time on the runtime,
+    which keeps them in order without the worker waiting on a slow peer. The outcome comes
+    back as `Sent`, a message the successor couldn't take goes to the next successor. One that
+    timed out isn't sent again, the successor may have handled it. A roll call would never
+    finish without its messages though, so those are kept until the heartbeats tell: they go
+    to the next successor if the one that timed out is dropped as down, and are forgotten once
+    it answers a heartbeat.
+
+    Leaders are elected the Chang–Roberts way: a node starting an election sends itself as the
+    candidate, every node forwards a higher candidate and replaces a lower one with itself
+    unless it already took part, a node getting its own candidacy back is elected and sends
+    `Elected` around the ring. The node that loses the leader as its successor starts the
+    next election.
+
+    The lock service passes a single token around the ring, the leader makes it and a node
+    that needs it in a ring without a leader starts an election first. A node grants one
+    waiting client a lock when the token arrives and passes the token on once the lock is
+    released or its lease runs out. A node with no client waiting keeps the token, one without
+    the token sends a request around the ring to the holder, which adds the node to the
+    token's requesters and passes the token on. Every node passes on a token with requesters,
+    serving its own waiting clients first. The leader sends a probe around the ring when it
+    hasn't seen the token for a while, a node marks the probe found if it holds the token or
+    has seen a newer one than the leader. Messages to a successor keep their order, so the
+    probe can't overtake a token in flight. A probe coming back unfound means the token died
+    with a node, the leader regenerates it with a higher generation and any older token still
+    around is dropped where it arrives. A node holding a lock refuses to leave until it's
+    released or its lease runs out, a leaving node passes the token on.
+*/
+struct Worker {
+    addr: SocketAddr,
+    id: u64,
+    state: Arc<NodeState>,
+    remote: Remote,
+    sender: Sender<Action>,
+    runtime: Runtime,
+    outbox: VecDeque<Message>,
+    sending: bool,
+    awaiting_heartbeat: bool,
+    missed_heartbeats: u32,
+    election_due: bool,
+    /// Held from the moment the token arrives until it's passed on
+    token: Option<Token>,
+    /// The newest token seen, as generation and sequence
+    seen: (u64, u64),
+    token_seen_at: Instant,
+    probe_started: Option<Instant>,
+    /// The id of the lock granted with the token
+    lock: Option<String>,
+    /// When the node last asked for the token for its waiting clients
+    requested_at: Option<Instant>,
+    waiting: VecDeque<(Lock, oneshot::Sender<Lock>)>,
+    dropped: Vec<Dropped>,
+    /// Roll-call messages that timed out, with the successor they were sent to
+    unconfirmed: Vec<(SocketAddr, Message)>,
+    leaving: bool,
+    stopped: bool,
+    tls: Option<ClientTls>,
+}
+
+impl Worker {
+    fn new(
+        addr: SocketAddr,
+        id: u64,
+        next: SocketAddr,
+        state: Arc<NodeState>,
+        sender: Sender<Action>,
+        runtime: Runtime,
+        tls: Option<ClientTls>,
+    ) -> Result<Self, Error> {
+        *state.successors.write().unwrap() = vec![next];
+        Ok(Self {
+            addr,
+            id,
+            state,
+            remote: Remote::connect(next, tls.as_ref())?,
+            sender,
+            runtime,
+            outbox: VecDeque::new(),
+            sending: false,
+            awaiting_heartbeat: false,
+            missed_heartbeats: 0,
+            election_due: false,
+            token: None,
+            seen: (0, 0),
+            token_seen_at: Instant::now(),
+            probe_started: None,
+            lock: None,
+            requested_at: None,
+            waiting: VecDeque::new(),
+            dropped: Vec::new(),
+            unconfirmed: Vec::new(),
+            leaving: false,
+            stopped: false,
+            tls,
+        })
+    }
+
+    fn connect(&self, addr: SocketAddr) -> Result<Remote, Error> {
+        Ok(Remote::connect(addr, self.tls.as_ref())?)
+    }
+
+    fn in_roll_call(&self, id: &str) -> bool {
+        self.state.roll_calls.lock().unwrap().contains(id)
+    }
+
+    fn next(&self) -> SocketAddr {
+        self.state.successors.read().unwrap()[0]
+    }
+
+    fn successors(&self) -> Vec<SocketAddr> {
+        self.state.successors.read().unwrap().clone()
+    }
+
+    fn in_election(&self) -> bool {
+        self.state.in_election.load(Ordering::SeqCst)
+    }
+
+    fn set_in_election(&self, in_election: bool) {
+        self.state.in_election.store(in_election, Ordering::SeqCst);
+    }
+
+    /// Tells the `WatchEvents` streams about a state transition
+    fn emit(&self, kind: &str, detail: String) {
+        let mut event = Event::new();
+        event.set_node(self.addr.to_string());
+        event.set_kind(kind.to_owned());
+        event.set_detail(detail);
+        self.state.publish(event);
+    }
+
+    fn fail(&self, err: String) {
+        *self.state.last_error.lock().unwrap() = Some((err, SystemTime::now()));
+    }
+
+    fn run(mut self, receiver: Receiver<Action>) -> Result<(), Error> {
+        let result = self.handle_all(receiver);
+        self.state.stop_watchers();
+        result
+    }
+
+    fn handle_all(&mut self, receiver: Receiver<Action>) -> Result<(), Error> {
+        for action in receiver.iter() {
+            *self
+                .state
+                .handled
+                .lock()
+                .unwrap()
+                .entry(action.name())
+                .or_insert(0) += 1;
+            if let Err(err) = self.handle(action) {
+                self.fail(err.to_string());
+                return Err(err);
+            }
+            if self.election_due {
+                self.election_due = false;
+                self.start_election();
+            }
+            // A leaving node waits for its notice to be delivered, a killed one doesn't
+            if self.stopped || (self.leaving && self.outbox.is_empty()) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn handle(&mut self, action: Action) -> Result<(), Error> {
+        match action {
+            Action::StartRollCall(call) => {
+                if !self.in_roll_call(call.get_id()) {
+                    debug!("ON {}", call.get_id());
+                    let id = call.get_id().to_owned();
+                    self.state.roll_calls.lock().unwrap().insert(id.clone());
+                    self.emit("ON", id);
+                    self.forward(Message::StartRollCall(call));
+                } else if call.get_initiator() == self.addr.to_string() {
+                    // Back at the initiator, the marking round starts
+                    self.mark_itself(call);
+                } else {
+                    // Passed on again after a successor timed out although it had taken it
+                    debug!("DUPLICATE {}", call.get_id());
+                }
+            }
+            Action::MarkItself(mut call) => {
+                let address = self.addr.to_string();
+                if self.in_roll_call(call.get_id()) {
+                    self.mark_itself(call);
+                } else if call.get_initiator() == address {
+                    debug!("TALLY {}", call.get_id());
+                    self.emit("TALLY", call.get_id().to_owned());
+                    let mut tally = Tally::new();
+                    tally.set_id(call.take_id());
+                    tally.set_marked(call.take_marked());
+                    self.state.tallies.complete(tally);
+                } else if call.get_marked().contains(&address) {
+                    // Marked here already and around the ring again, the initiator is gone
+                    debug!("DROP {}", call.get_id());
+                } else {
+                    // Not in this roll call, say it started before the node joined
+                    debug!("SKIP {}", call.get_id());
+                    self.emit("SKIP", call.get_id().to_owned());
+                    self.forward(Message::MarkItself(call));
+                }
+            }
+            Action::Join { node, reply } => {
+                debug!("JOIN {}", node);
+                self.forget_dropped(node);
+                // A restarted node may join again before it was found down
+                let mut successors = self.successors();
+                successors.retain(|&other| other != node);
+                let next = successors.first().cloned().unwrap_or(self.addr);
+                if reply.send(next).is_ok() {
+                    successors.insert(0, node);
+                    self.set_successors(successors)?;
+                    // A fresh connection, the old one may have gone down with the node
+                    self.remote = self.connect(node)?;
+                    self.missed_heartbeats = 0;
+                }
+            }
+            Action::Leave { reply } => {
+                // Dropping the lock would let another client take it while its holder still
+                // thinks it has it, so the holder releases it or the lease runs out first
+                if let Some(ref id) = self.lock {
+                    warn!("NOT LEAVING, {} IS HELD", id);
+                    let _ = reply.send(false);
+                    return Ok(());
+                }
+                let _ = reply.send(true);
+                debug!("LEAVE");
+                self.emit("LEAVE", String::new());
+                self.leaving = true;
+                // The notice travels around the ring to the predecessor
+                let next = self.next();
+                if next != self.addr {
+                    self.pass_token();
+                    self.forward(Message::Left {
+                        node: self.addr,
+                        successor: next,
+                        origin: None,
+                    });
+                }
+            }
+            Action::Left {
+                node,
+                successor,
+                origin,
+            } => {
+                self.forget_dropped(node);
+                self.unconfirmed.retain(|&(to, _)| to != node);
+                let mut successors = self.successors();
+                let was_next = successors[0] == node;
+                successors.retain(|&other| other != node);
+                self.lose(node, was_next);
+                if was_next {
+                    info!("{} LEFT, ROUTING TO {}", node, successor);
+                    if successors.first() != Some(&successor) {
+                        successors.insert(0, successor);
+                    }
+                    self.set_successors(successors)?;
+                } else if node != self.addr {
+                    self.set_successors(successors)?;
+                    match origin {
+                        // Around the ring without finding the node before `node`, which
+                        // must be gone as well
+                        Some(origin) if origin == self.addr => {
+                            debug!("{} LEFT, NOTICE CAME BACK", node);
+                        }
+                        _ => self.forward(Message::Left {
+                            node,
+                            successor,
+                            origin: Some(origin.unwrap_or(self.addr)),
+                        }),
+                    }
+                }
+            }
+            Action::Heartbeat => {
+                self.state.tallies.expire();
+                self.heartbeat();
+                // Clients that gave up don't need the token any more
+                self.waiting.retain(|(_, reply)| !reply.is_canceled());
+                if !self.waiting.is_empty() && self.token.is_none() {
+                    self.ensure_leader();
+                    self.request_token();
+                }
+                self.check_token();
+            }
+            Action::HeartbeatReply { from, result } => {
+                self.awaiting_heartbeat = false;
+                // Alive, so it took the messages it didn't confirm
+                if result.is_ok() {
+                    self.unconfirmed.retain(|&(to, _)| to != from);
+                }
+                // A reply from a former successor says nothing about the current one
+                if from != self.next() {
+                    return Ok(());
+                }
+                match result {
+                    Ok(theirs) => {
+                        self.missed_heartbeats = 0;
+                        // Past this node the list would only go around the ring again
+                        let mut successors = vec![from];
+                        successors.extend(theirs.into_iter().take_while(|&node| node != self.addr));
+                        self.set_successors(successors)?;
+                    }
+                    Err(err) => {
+                        self.missed_heartbeats += 1;
+                        debug!("MISSED HEARTBEAT {} OF {}", self.missed_heartbeats, from);
+                        self.fail(format!("{} missed a heartbeat: {}", from, err));
+                        if self.missed_heartbeats >= MAX_MISSED_HEARTBEATS {
+                            self.drop_next(&err)?;
+                        }
+                    }
+                }
+            }
+            Action::RecheckReply { node, result } => {
+                let theirs = match result {
+                    Ok(theirs) => theirs,
+                    Err(_) => {
+                        if let Some(dropped) = self.dropped.iter_mut().find(|d| d.node == node) {
+                            dropped.checking = false;
+                        }
+                        return Ok(());
+                    }
+                };
+                self.forget_dropped(node);
+                // Still in its old place if its successor is the node that took over from it
+                let mut successors = self.successors();
+                let next = successors[0];
+                if next == self.addr || theirs.first() == Some(&next) {
+                    info!("{} IS BACK", node);
+                    self.emit("UP", node.to_string());
+                    successors.insert(0, node);
+                    self.set_successors(successors)?;
+                }
+            }
+            Action::StartElection => self.start_election(),
+            Action::Elect(candidate) => {
+                let ours = self.candidate();
+                match key(&candidate).cmp(&key(&ours)) {
+                    cmp::Ordering::Equal => {
+                        info!("ELECTED {}", self.addr);
+                        self.set_in_election(false);
+                        self.set_leader(Some(ours.clone()));
+                        self.forward(Message::Elected(ours));
+                        if self.token.is_none() {
+                            self.probe_token();
+                        }
+                    }
+                    cmp::Ordering::Greater => {
+                        self.set_in_election(true);
+                        self.forward(Message::Elect(candidate));
+                    }
+                    cmp::Ordering::Less if !self.in_election() => self.start_election(),
+                    cmp::Ordering::Less => {
+                        debug!("DISCARD CANDIDATE {}", candidate.get_address());
+                    }
+                }
+            }
+            Action::Elected(leader) => {
+                self.set_in_election(false);
+                if leader.get_address() != self.addr.to_string() {
+                    self.set_leader(Some(leader.clone()));
+                    self.forward(Message::Elected(leader));
+                }
+            }
+            Action::PassToken(token) => {
+                let version = (token.get_generation(), token.get_sequence());
+                if version <= self.seen {
+                    debug!("STALE TOKEN {:?}", version);
+                    return Ok(());
+                }
+                trace!("TOKEN {:?}", version);
+                self.seen = version;
+                self.token_seen_at = Instant::now();
+                self.token = Some(token);
+                self.use_token();
+            }
+            Action::ProbeToken(mut probe) => {
+                let version = (probe.get_generation(), probe.get_sequence());
+                if probe.get_initiator() == self.addr.to_string() {
+                    self.probe_started = None;
+                    // Kept by a node nobody asked it from, no need to look again for a while
+                    if probe.get_found() {
+                        self.token_seen_at = Instant::now();
+                    }
+                    // The token may have passed by while the probe went around
+                    let lost = !probe.get_found() && self.seen <= version && self.token.is_none();
+                    if lost && self.is_leader() {
+                        self.regenerate_token();
+                    }
+                } else if self.is_leader_addr(probe.get_initiator()) {
+                    if self.token.is_some() || self.seen > version {
+                        probe.set_found(true);
+                    }
+                    self.forward(Message::ProbeToken(probe));
+                } else {
+                    debug!("DISCARD PROBE FROM {}", probe.get_initiator());
+                }
+            }
+            Action::RequestToken(requester) => {
+                if requester == self.addr {
+                    // Around the ring without finding the token, it's on its way or lost
+                    debug!("TOKEN REQUEST CAME BACK");
+                    return Ok(());
+                }
+                match self.token {
+                    Some(ref mut token) => {
+                        let requester = requester.to_string();
+                        if !token.get_requesters().contains(&requester) {
+                            token.mut_requesters().push(requester);
+                        }
+                    }
+                    None => {
+                        self.forward(Message::RequestToken(requester));
+                        return Ok(());
+                    }
+                }
+                // A lock held here passes it on once released
+                if self.lock.is_none() {
+                    self.pass_token();
+                }
+            }
+            Action::LeaseExpired(id) => {
+                if self.lock.as_ref() == Some(&id) {
+                    warn!("LEASE OF {} EXPIRED", id);
+                    self.emit("EXPIRED", id);
+                    self.lock = None;
+                    self.hand_on_token();
+                }
+            }
+            Action::Acquire { lock, reply } => {
+                self.waiting.push_back((lock, reply));
+                if self.token.is_none() {
+                    self.ensure_leader();
+                    self.request_token();
+                } else if self.lock.is_none() {
+                    self.use_token();
+                }
+            }
+            Action::Release { lock, reply } => {
+                let held = match self.lock {
+                    Some(ref id) => id == lock.get_id(),
+                    None => false,
+                };
+                let _ = reply.send(held);
+                if held {
+                    debug!("UNLOCK {}", lock.get_id());
+                    self.emit("UNLOCK", lock.get_id().to_owned());
+                    self.lock = None;
+                    self.hand_on_token();
+                }
+            }
+            Action::Stop => {
+                debug!("STOP");
+                self.emit("STOP", String::new());
+                self.stopped = true;
+            }
+            Action::Sent { to, error } => {
+                self.sending = false;
+                match error {
+                    None => {
+                        self.outbox.pop_front();
+                    }
+                    Some(ref err) if !err.is_retryable() => {
+                        warn!("{} refused a message: {}", to, err);
+                        self.fail(format!("{} refused a message: {}", to, err));
+                        self.outbox.pop_front();
+                    }
+                    Some(ref err) if to == self.addr => {
+                        warn!("can't reach itself: {}", err);
+                        self.fail(format!("can't reach itself: {}", err));
+                        self.outbox.pop_front();
+                    }
+                    // The successor may have taken it, passed to the next one as well it would
+                    // go around twice. Heartbeats tell whether the successor is down
+                    Some(ref err @ RemoteError::Timeout(_)) => {
+                        warn!("{} didn't confirm a message: {}", to, err);
+                        self.fail(format!("{} didn't confirm a message: {}", to, err));
+                        match self.outbox.pop_front() {
+                            Some(message @ Message::StartRollCall(_))
+                            | Some(message @ Message::MarkItself(_)) => {
+                                self.unconfirmed.push((to, message));
+                            }
+                            _ => {}
+                        }
+                    }
+                    // Otherwise the message goes to the next successor
+                    Some(ref err) => {
+                        if to == self.next() {
+                            self.drop_next(err)?;
+                        }
+                    }
+                }
+                self.flush();
+            }
+        }
+        Ok(())
+    }
+
+    fn mark_itself(&mut self, mut call: RollCall) {
+        call.mut_marked().push(self.addr.to_string());
+        debug!("OFF {}", call.get_id());
+        self.state.roll_calls.lock().unwrap().remove(call.get_id());
+        self.emit("OFF", call.get_id().to_owned());
+        self.forward(Message::MarkItself(call));
+    }
+
+    fn candidate(&self) -> Candidate {
+        let mut candidate = Candidate::new();
+        candidate.set_id(self.id);
+        candidate.set_address(self.addr.to_string());
+        candidate
+    }
+
+    fn start_election(&mut self) {
+        debug!("ELECTION");
+        self.emit("ELECTION", self.addr.to_string());
+        self.set_in_election(true);
+        let candidate = self.candidate();
+        self.forward(Message::Elect(candidate));
+    }
+
+    fn set_leader(&self, leader: Option<Candidate>) {
+        match leader {
+            Some(ref leader) => info!("LEADER {}", leader.get_address()),
+            None => info!("NO LEADER"),
+        }
+        let address = leader
+            .as_ref()
+            .map(|leader| leader.get_address().to_owned());
+        self.emit("LEADER", address.unwrap_or_default());
+        *self.state.leader.write().unwrap() = leader;
+    }
+
+    fn is_leader(&self) -> bool {
+        self.is_leader_addr(&self.addr.to_string())
+    }
+
+    fn is_leader_addr(&self, address: &str) -> bool {
+        match *self.state.leader.read().unwrap() {
+            Some(ref leader) => leader.get_address() == address,
+            None => false,
+        }
+    }
+
+    /// Grants the token to the first client still waiting. Without any the token goes on if
+    /// another node asked for it and stays here otherwise
+    fn use_token(&mut self) {
+        self.requested_at = None;
+        let addr = self.addr.to_string();
+        if let Some(ref mut token) = self.token {
+            token
+                .mut_requesters()
+                .retain(|requester| *requester != addr);
+        }
+        while let Some((mut lock, reply)) = self.waiting.pop_front() {
+            let id = lock.get_id().to_owned();
+            lock.set_lease_secs(LOCK_LEASE.as_secs());
+            // The client may have given up
+            if reply.send(lock).is_ok() {
+                debug!("LOCK {}", id);
+                self.emit("LOCK", id.clone());
+                self.lock = Some(id.clone());
+                let sender = self.sender.clone();
+                let lease = Delay::new(Instant::now() + LOCK_LEASE).then(move |_| {
+                    let _ = sender.send(Action::LeaseExpired(id));
+                    Ok(())
+                });
+                self.runtime.executor().spawn(lease);
+                return;
+            }
+        }
+        if self.token_wanted() {
+            self.pass_token();
+        }
+    }
+
+    /// Once a lock is given back, other nodes that asked for the token get their turn before
+    /// the next client here
+    fn hand_on_token(&mut self) {
+        if self.token_wanted() {
+            self.pass_token();
+        } else {
+            self.use_token();
+        }
+    }
+
+    /// Whether another node asked for the token held here
+    fn token_wanted(&self) -> bool {
+        match self.token {
+            Some(ref token) => !token.get_requesters().is_empty(),
+            None => false,
+        }
+    }
+
+    fn pass_token(&mut self) {
+        if let Some(mut token) = self.token.take() {
+            token.set_sequence(token.get_sequence() + 1);
+            self.forward(Message::PassToken(token));
+        }
+    }
+
+    /// Asks the ring for the token, again only once an earlier request had time to get it here
+    fn request_token(&mut self) {
+        if let Some(requested) = self.requested_at {
+            if requested.elapsed() < TOKEN_TIMEOUT {
+                return;
+            }
+        }
+        trace!("REQUEST TOKEN");
+        self.requested_at = Some(Instant::now());
+        let addr = self.addr;
+        self.forward(Message::RequestToken(addr));
+    }
+
+    /// Only a leader makes the first token, or a new one once it's lost, so a node that needs
+    /// the token in a ring without a leader starts an election
+    fn ensure_leader(&mut self) {
+        let no_leader = self.state.leader.read().unwrap().is_none();
+        if no_leader && !self.in_election() {
+            self.start_election();
+        }
+    }
+
+    /// On the leader, probes for a token it hasn't seen in a while
+    fn check_token(&mut self) {
+        if !self.is_leader() || self.token.is_some() {
+            return;
+        }
+        if self.token_seen_at.elapsed() < TOKEN_TIMEOUT {
+            return;
+        }
+        match self.probe_started {
+            // The probe itself may be lost
+            Some(started) if started.elapsed() < TOKEN_TIMEOUT => {}
+            _ => self.probe_token(),
+        }
+    }
+
+    fn probe_token(&mut self) {
+        debug!("PROBE {:?}", self.seen);
+        self.probe_started = Some(Instant::now());
+        let mut probe = TokenProbe::new();
+        probe.set_initiator(self.addr.to_string());
+        probe.set_generation(self.seen.0);
+        probe.set_sequence(self.seen.1);
+        self.forward(Message::ProbeToken(probe));
+    }
+
+    fn regenerate_token(&mut self) {
+        let generation = self.seen.0 + 1;
+        info!("TOKEN {}", generation);
+        self.emit("TOKEN", generation.to_string());
+        let mut token = Token::new();
+        token.set_generation(generation);
+        self.seen = (generation, 0);
+        self.token_seen_at = Instant::now();
+        self.token = Some(token);
+        self.use_token();
+    }
+
+    /// Forgets `node` as the leader, its predecessor starts a new election
+    fn lose(&mut self, node: SocketAddr, was_next: bool) {
+        let lost_leader = match *self.state.leader.read().unwrap() {
+            Some(ref leader) => leader.get_address() == node.to_string(),
+            None => false,
+        };
+        if lost_leader {
+            self.set_leader(None);
+            self.election_due = was_next;
+        }
+    }
+
+    fn forward(&mut self, message: Message) {
+        self.outbox.push_back(message);
+        self.flush();
+    }
+
+    /// Sends the head of the outbox unless a message is already on its way
+    fn flush(&mut self) {
+        if self.sending {
+            return;
+        }
+        let message = match self.outbox.front() {
+            Some(message) => message.clone(),
+            None => return,
+        };
+        self.sending = true;
+        let to = self.next();
+        let sender = self.sender.clone();
+        let sent = message.send(&self.remote).then(move |result| {
+            let _ = sender.send(Action::Sent {
+                to,
+                error: result.err(),
+            });
+            Ok(())
+        });
+        self.runtime.executor().spawn(sent);
+    }
+
+    fn heartbeat(&mut self) {
+        self.recheck_dropped();
+        let next = self.next();
+        if next == self.addr || self.awaiting_heartbeat {
+            return;
+        }
+        self.awaiting_heartbeat = true;
+        let sender = self.sender.clone();
+        let reply = self.remote.heartbeat().then(move |result| {
+            let _ = sender.send(Action::HeartbeatReply { from: next, result });
+            Ok(())
+        });
+        self.runtime.executor().spawn(reply);
+    }
+
+    /// Routes around the nearest successor, with no successor left the node is a ring of its
+    /// own until another node joins. The successor keeps getting heartbeats for a while and
+    /// is taken back if it answers
+    fn drop_next(&mut self, err: &RemoteError) -> Result<(), Error> {
+        let mut successors = self.successors();
+        let dead = successors.remove(0);
+        self.set_successors(successors)?;
+        info!("{} IS DOWN ({}), ROUTING TO {}", dead, err, self.next());
+        self.emit("DOWN", dead.to_string());
+        self.fail(format!("{} is down: {}", dead, err));
+        self.lose(dead, true);
+        self.forget_dropped(dead);
+        self.dropped.push(Dropped {
+            node: dead,
+            at: Instant::now(),
+            checking: false,
+        });
+        self.reroute_unconfirmed(dead);
+        Ok(())
+    }
+
+    /// Puts the roll-call messages `dead` didn't confirm back in the outbox, ahead of the
+    /// messages that came after them and behind the one on its way
+    fn reroute_unconfirmed(&mut self, dead: SocketAddr) {
+        let (rerouted, kept): (Vec<_>, Vec<_>) =
+            self.unconfirmed.drain(..).partition(|&(to, _)| to == dead);
+        self.unconfirmed = kept;
+        let at = if self.sending { 1 } else { 0 };
+        for (_, message) in rerouted.into_iter().rev() {
+            debug!("REROUTE TO {}", self.next());
+            self.outbox.insert(at, message);
+        }
+        self.flush();
+    }
+
+    /// Sends a heartbeat to every successor dropped not long ago that isn't still answering
+    /// the last one
+    fn recheck_dropped(&mut self) {
+        self.dropped
+            .retain(|dropped| dropped.at.elapsed() < RECHECK_DROPPED);
+        for dropped in self.dropped.iter_mut().filter(|dropped| !dropped.checking) {
+            let node = dropped.node;
+            let remote = match Remote::connect(node, self.tls.as_ref()) {
+                Ok(remote) => remote,
+                Err(err) => {
+                    debug!("CAN'T RECHECK {}: {}", node, err);
+                    continue;
+                }
+            };
+            dropped.checking = true;
+            let sender = self.sender.clone();
+            let reply = remote.heartbeat().then(move |result| {
+                let _ = sender.send(Action::RecheckReply { node, result });
+                Ok(())
+            });
+            self.runtime.executor().spawn(reply);
+        }
+    }
+
+    fn forget_dropped(&mut self, node: SocketAddr) {
+        self.dropped.retain(|dropped| dropped.node != node);
+    }
+
+    fn set_successors(&mut self, mut successors: Vec<SocketAddr>) -> Result<(), Error> {
+        successors.truncate(SUCCESSORS);
+        if successors.is_empty() {
+            successors.push(self.addr);
+        }
+        if successors[0] != self.next() {
+            info!("SUCCESSOR {} -> {}", self.next(), successors[0]);
+            self.emit("SUCCESSOR", successors[0].to_string());
+            self.remote = self.connect(successors[0])?;
+            self.missed_heartbeats = 0;
+        }
+        *self.state.successors.write().unwrap() = successors;
+        Ok(())
+    }
+}
+
+/// A successor dropped as down, still checked on heartbeats until `RECHECK_DROPPED` passed
+struct Dropped {
+    node: SocketAddr,
+    at: Instant,
+    /// A heartbeat to it is on its way
+    checking: bool,
+}
+
+/// Candidates compare by id, the address breaks ties between nodes configured with the same id
+fn key(candidate: &Candidate) -> (u64, &str) {
+    (candidate.get_id(), candidate.get_address())
+}
@@ -152,10 +152,3312 @@ impl ::protobuf::reflect::ProtobufValue for Empty {
     }
 }
 
+#[derive(PartialEq,Clone,Default)]
+pub struct RollCall {
+    // message fields
+    pub id: ::std::string::String,
+    pub initiator: ::std::string::String,
+    pub marked: ::protobuf::RepeatedField<::std::string::String>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a RollCall {
+    fn default() -> &'a RollCall {
+        <RollCall as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl RollCall {
+    pub fn new() -> RollCall {
+        ::std::default::Default::default()
+    }
+
+    // string id = 1;
+
+
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+    pub fn clear_id(&mut self) {
+        self.id.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_id(&mut self, v: ::std::string::String) {
+        self.id = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_id(&mut self) -> &mut ::std::string::String {
+        &mut self.id
+    }
+
+    // Take field
+    pub fn take_id(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.id, ::std::string::String::new())
+    }
+
+    // string initiator = 2;
+
+
+    pub fn get_initiator(&self) -> &str {
+        &self.initiator
+    }
+    pub fn clear_initiator(&mut self) {
+        self.initiator.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_initiator(&mut self, v: ::std::string::String) {
+        self.initiator = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_initiator(&mut self) -> &mut ::std::string::String {
+        &mut self.initiator
+    }
+
+    // Take field
+    pub fn take_initiator(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.initiator, ::std::string::String::new())
+    }
+
+    // repeated string marked = 3;
+
+
+    pub fn get_marked(&self) -> &[::std::string::String] {
+        &self.marked
+    }
+    pub fn clear_marked(&mut self) {
+        self.marked.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_marked(&mut self, v: ::protobuf::RepeatedField<::std::string::String>) {
+        self.marked = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_marked(&mut self) -> &mut ::protobuf::RepeatedField<::std::string::String> {
+        &mut self.marked
+    }
+
+    // Take field
+    pub fn take_marked(&mut self) -> ::protobuf::RepeatedField<::std::string::String> {
+        ::std::mem::replace(&mut self.marked, ::protobuf::RepeatedField::new())
+    }
+}
+
+impl ::protobuf::Message for RollCall {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.id)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.initiator)?;
+                },
+                3 => {
+                    ::protobuf::rt::read_repeated_string_into(wire_type, is, &mut self.marked)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.id.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.id);
+        }
+        if !self.initiator.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.initiator);
+        }
+        for value in &self.marked {
+            my_size += ::protobuf::rt::string_size(3, &value);
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.id.is_empty() {
+            os.write_string(1, &self.id)?;
+        }
+        if !self.initiator.is_empty() {
+            os.write_string(2, &self.initiator)?;
+        }
+        for v in &self.marked {
+            os.write_string(3, &v)?;
+        };
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> RollCall {
+        RollCall::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "id",
+                    |m: &RollCall| { &m.id },
+                    |m: &mut RollCall| { &mut m.id },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "initiator",
+                    |m: &RollCall| { &m.initiator },
+                    |m: &mut RollCall| { &mut m.initiator },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "marked",
+                    |m: &RollCall| { &m.marked },
+                    |m: &mut RollCall| { &mut m.marked },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<RollCall>(
+                    "RollCall",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static RollCall {
+        static mut instance: ::protobuf::lazy::Lazy<RollCall> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const RollCall,
+        };
+        unsafe {
+            instance.get(RollCall::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for RollCall {
+    fn clear(&mut self) {
+        self.id.clear();
+        self.initiator.clear();
+        self.marked.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for RollCall {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for RollCall {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct Tally {
+    // message fields
+    pub id: ::std::string::String,
+    pub marked: ::protobuf::RepeatedField<::std::string::String>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a Tally {
+    fn default() -> &'a Tally {
+        <Tally as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl Tally {
+    pub fn new() -> Tally {
+        ::std::default::Default::default()
+    }
+
+    // string id = 1;
+
+
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+    pub fn clear_id(&mut self) {
+        self.id.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_id(&mut self, v: ::std::string::String) {
+        self.id = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_id(&mut self) -> &mut ::std::string::String {
+        &mut self.id
+    }
+
+    // Take field
+    pub fn take_id(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.id, ::std::string::String::new())
+    }
+
+    // repeated string marked = 2;
+
+
+    pub fn get_marked(&self) -> &[::std::string::String] {
+        &self.marked
+    }
+    pub fn clear_marked(&mut self) {
+        self.marked.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_marked(&mut self, v: ::protobuf::RepeatedField<::std::string::String>) {
+        self.marked = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_marked(&mut self) -> &mut ::protobuf::RepeatedField<::std::string::String> {
+        &mut self.marked
+    }
+
+    // Take field
+    pub fn take_marked(&mut self) -> ::protobuf::RepeatedField<::std::string::String> {
+        ::std::mem::replace(&mut self.marked, ::protobuf::RepeatedField::new())
+    }
+}
+
+impl ::protobuf::Message for Tally {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.id)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_repeated_string_into(wire_type, is, &mut self.marked)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.id.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.id);
+        }
+        for value in &self.marked {
+            my_size += ::protobuf::rt::string_size(2, &value);
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.id.is_empty() {
+            os.write_string(1, &self.id)?;
+        }
+        for v in &self.marked {
+            os.write_string(2, &v)?;
+        };
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> Tally {
+        Tally::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "id",
+                    |m: &Tally| { &m.id },
+                    |m: &mut Tally| { &mut m.id },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "marked",
+                    |m: &Tally| { &m.marked },
+                    |m: &mut Tally| { &mut m.marked },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<Tally>(
+                    "Tally",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static Tally {
+        static mut instance: ::protobuf::lazy::Lazy<Tally> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const Tally,
+        };
+        unsafe {
+            instance.get(Tally::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for Tally {
+    fn clear(&mut self) {
+        self.id.clear();
+        self.marked.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for Tally {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Tally {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct JoinRequest {
+    // message fields
+    pub address: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a JoinRequest {
+    fn default() -> &'a JoinRequest {
+        <JoinRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl JoinRequest {
+    pub fn new() -> JoinRequest {
+        ::std::default::Default::default()
+    }
+
+    // string address = 1;
+
+
+    pub fn get_address(&self) -> &str {
+        &self.address
+    }
+    pub fn clear_address(&mut self) {
+        self.address.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_address(&mut self, v: ::std::string::String) {
+        self.address = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_address(&mut self) -> &mut ::std::string::String {
+        &mut self.address
+    }
+
+    // Take field
+    pub fn take_address(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.address, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for JoinRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.address)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.address.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.address);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.address.is_empty() {
+            os.write_string(1, &self.address)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> JoinRequest {
+        JoinRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "address",
+                    |m: &JoinRequest| { &m.address },
+                    |m: &mut JoinRequest| { &mut m.address },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<JoinRequest>(
+                    "JoinRequest",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static JoinRequest {
+        static mut instance: ::protobuf::lazy::Lazy<JoinRequest> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const JoinRequest,
+        };
+        unsafe {
+            instance.get(JoinRequest::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for JoinRequest {
+    fn clear(&mut self) {
+        self.address.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for JoinRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for JoinRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct JoinReply {
+    // message fields
+    pub successor: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a JoinReply {
+    fn default() -> &'a JoinReply {
+        <JoinReply as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl JoinReply {
+    pub fn new() -> JoinReply {
+        ::std::default::Default::default()
+    }
+
+    // string successor = 1;
+
+
+    pub fn get_successor(&self) -> &str {
+        &self.successor
+    }
+    pub fn clear_successor(&mut self) {
+        self.successor.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_successor(&mut self, v: ::std::string::String) {
+        self.successor = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_successor(&mut self) -> &mut ::std::string::String {
+        &mut self.successor
+    }
+
+    // Take field
+    pub fn take_successor(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.successor, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for JoinReply {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.successor)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.successor.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.successor);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.successor.is_empty() {
+            os.write_string(1, &self.successor)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> JoinReply {
+        JoinReply::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "successor",
+                    |m: &JoinReply| { &m.successor },
+                    |m: &mut JoinReply| { &mut m.successor },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<JoinReply>(
+                    "JoinReply",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static JoinReply {
+        static mut instance: ::protobuf::lazy::Lazy<JoinReply> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const JoinReply,
+        };
+        unsafe {
+            instance.get(JoinReply::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for JoinReply {
+    fn clear(&mut self) {
+        self.successor.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for JoinReply {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for JoinReply {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct LeaveRequest {
+    // message fields
+    pub address: ::std::string::String,
+    pub successor: ::std::string::String,
+    pub origin: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a LeaveRequest {
+    fn default() -> &'a LeaveRequest {
+        <LeaveRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl LeaveRequest {
+    pub fn new() -> LeaveRequest {
+        ::std::default::Default::default()
+    }
+
+    // string address = 1;
+
+
+    pub fn get_address(&self) -> &str {
+        &self.address
+    }
+    pub fn clear_address(&mut self) {
+        self.address.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_address(&mut self, v: ::std::string::String) {
+        self.address = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_address(&mut self) -> &mut ::std::string::String {
+        &mut self.address
+    }
+
+    // Take field
+    pub fn take_address(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.address, ::std::string::String::new())
+    }
+
+    // string successor = 2;
+
+
+    pub fn get_successor(&self) -> &str {
+        &self.successor
+    }
+    pub fn clear_successor(&mut self) {
+        self.successor.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_successor(&mut self, v: ::std::string::String) {
+        self.successor = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_successor(&mut self) -> &mut ::std::string::String {
+        &mut self.successor
+    }
+
+    // Take field
+    pub fn take_successor(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.successor, ::std::string::String::new())
+    }
+
+    // string origin = 3;
+
+
+    pub fn get_origin(&self) -> &str {
+        &self.origin
+    }
+    pub fn clear_origin(&mut self) {
+        self.origin.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_origin(&mut self, v: ::std::string::String) {
+        self.origin = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_origin(&mut self) -> &mut ::std::string::String {
+        &mut self.origin
+    }
+
+    // Take field
+    pub fn take_origin(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.origin, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for LeaveRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.address)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.successor)?;
+                },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.origin)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.address.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.address);
+        }
+        if !self.successor.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.successor);
+        }
+        if !self.origin.is_empty() {
+            my_size += ::protobuf::rt::string_size(3, &self.origin);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.address.is_empty() {
+            os.write_string(1, &self.address)?;
+        }
+        if !self.successor.is_empty() {
+            os.write_string(2, &self.successor)?;
+        }
+        if !self.origin.is_empty() {
+            os.write_string(3, &self.origin)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> LeaveRequest {
+        LeaveRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "address",
+                    |m: &LeaveRequest| { &m.address },
+                    |m: &mut LeaveRequest| { &mut m.address },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "successor",
+                    |m: &LeaveRequest| { &m.successor },
+                    |m: &mut LeaveRequest| { &mut m.successor },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "origin",
+                    |m: &LeaveRequest| { &m.origin },
+                    |m: &mut LeaveRequest| { &mut m.origin },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<LeaveRequest>(
+                    "LeaveRequest",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static LeaveRequest {
+        static mut instance: ::protobuf::lazy::Lazy<LeaveRequest> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const LeaveRequest,
+        };
+        unsafe {
+            instance.get(LeaveRequest::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for LeaveRequest {
+    fn clear(&mut self) {
+        self.address.clear();
+        self.successor.clear();
+        self.origin.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for LeaveRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for LeaveRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct Successors {
+    // message fields
+    pub addresses: ::protobuf::RepeatedField<::std::string::String>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a Successors {
+    fn default() -> &'a Successors {
+        <Successors as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl Successors {
+    pub fn new() -> Successors {
+        ::std::default::Default::default()
+    }
+
+    // repeated string addresses = 1;
+
+
+    pub fn get_addresses(&self) -> &[::std::string::String] {
+        &self.addresses
+    }
+    pub fn clear_addresses(&mut self) {
+        self.addresses.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_addresses(&mut self, v: ::protobuf::RepeatedField<::std::string::String>) {
+        self.addresses = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_addresses(&mut self) -> &mut ::protobuf::RepeatedField<::std::string::String> {
+        &mut self.addresses
+    }
+
+    // Take field
+    pub fn take_addresses(&mut self) -> ::protobuf::RepeatedField<::std::string::String> {
+        ::std::mem::replace(&mut self.addresses, ::protobuf::RepeatedField::new())
+    }
+}
+
+impl ::protobuf::Message for Successors {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_repeated_string_into(wire_type, is, &mut self.addresses)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        for value in &self.addresses {
+            my_size += ::protobuf::rt::string_size(1, &value);
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        for v in &self.addresses {
+            os.write_string(1, &v)?;
+        };
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> Successors {
+        Successors::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "addresses",
+                    |m: &Successors| { &m.addresses },
+                    |m: &mut Successors| { &mut m.addresses },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<Successors>(
+                    "Successors",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static Successors {
+        static mut instance: ::protobuf::lazy::Lazy<Successors> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const Successors,
+        };
+        unsafe {
+            instance.get(Successors::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for Successors {
+    fn clear(&mut self) {
+        self.addresses.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for Successors {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Successors {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct Candidate {
+    // message fields
+    pub id: u64,
+    pub address: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a Candidate {
+    fn default() -> &'a Candidate {
+        <Candidate as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl Candidate {
+    pub fn new() -> Candidate {
+        ::std::default::Default::default()
+    }
+
+    // uint64 id = 1;
+
+
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+    pub fn clear_id(&mut self) {
+        self.id = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_id(&mut self, v: u64) {
+        self.id = v;
+    }
+
+    // string address = 2;
+
+
+    pub fn get_address(&self) -> &str {
+        &self.address
+    }
+    pub fn clear_address(&mut self) {
+        self.address.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_address(&mut self, v: ::std::string::String) {
+        self.address = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_address(&mut self) -> &mut ::std::string::String {
+        &mut self.address
+    }
+
+    // Take field
+    pub fn take_address(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.address, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for Candidate {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.id = tmp;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.address)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.id != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.id, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.address.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.address);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if self.id != 0 {
+            os.write_uint64(1, self.id)?;
+        }
+        if !self.address.is_empty() {
+            os.write_string(2, &self.address)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> Candidate {
+        Candidate::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "id",
+                    |m: &Candidate| { &m.id },
+                    |m: &mut Candidate| { &mut m.id },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "address",
+                    |m: &Candidate| { &m.address },
+                    |m: &mut Candidate| { &mut m.address },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<Candidate>(
+                    "Candidate",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static Candidate {
+        static mut instance: ::protobuf::lazy::Lazy<Candidate> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const Candidate,
+        };
+        unsafe {
+            instance.get(Candidate::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for Candidate {
+    fn clear(&mut self) {
+        self.id = 0;
+        self.address.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for Candidate {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Candidate {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct Counter {
+    // message fields
+    pub action: ::std::string::String,
+    pub count: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a Counter {
+    fn default() -> &'a Counter {
+        <Counter as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl Counter {
+    pub fn new() -> Counter {
+        ::std::default::Default::default()
+    }
+
+    // string action = 1;
+
+
+    pub fn get_action(&self) -> &str {
+        &self.action
+    }
+    pub fn clear_action(&mut self) {
+        self.action.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_action(&mut self, v: ::std::string::String) {
+        self.action = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_action(&mut self) -> &mut ::std::string::String {
+        &mut self.action
+    }
+
+    // Take field
+    pub fn take_action(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.action, ::std::string::String::new())
+    }
+
+    // uint64 count = 2;
+
+
+    pub fn get_count(&self) -> u64 {
+        self.count
+    }
+    pub fn clear_count(&mut self) {
+        self.count = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_count(&mut self, v: u64) {
+        self.count = v;
+    }
+}
+
+impl ::protobuf::Message for Counter {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.action)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.count = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.action.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.action);
+        }
+        if self.count != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.count, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.action.is_empty() {
+            os.write_string(1, &self.action)?;
+        }
+        if self.count != 0 {
+            os.write_uint64(2, self.count)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> Counter {
+        Counter::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "action",
+                    |m: &Counter| { &m.action },
+                    |m: &mut Counter| { &mut m.action },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "count",
+                    |m: &Counter| { &m.count },
+                    |m: &mut Counter| { &mut m.count },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<Counter>(
+                    "Counter",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static Counter {
+        static mut instance: ::protobuf::lazy::Lazy<Counter> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const Counter,
+        };
+        unsafe {
+            instance.get(Counter::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for Counter {
+    fn clear(&mut self) {
+        self.action.clear();
+        self.count = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for Counter {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Counter {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct NodeStatus {
+    // message fields
+    pub address: ::std::string::String,
+    pub in_roll_call: bool,
+    pub in_election: bool,
+    pub successor: ::std::string::String,
+    pub leader: ::std::string::String,
+    pub handled: ::protobuf::RepeatedField<Counter>,
+    pub last_error: ::std::string::String,
+    pub roll_calls: ::protobuf::RepeatedField<::std::string::String>,
+    pub last_error_at: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a NodeStatus {
+    fn default() -> &'a NodeStatus {
+        <NodeStatus as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl NodeStatus {
+    pub fn new() -> NodeStatus {
+        ::std::default::Default::default()
+    }
+
+    // string address = 1;
+
+
+    pub fn get_address(&self) -> &str {
+        &self.address
+    }
+    pub fn clear_address(&mut self) {
+        self.address.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_address(&mut self, v: ::std::string::String) {
+        self.address = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_address(&mut self) -> &mut ::std::string::String {
+        &mut self.address
+    }
+
+    // Take field
+    pub fn take_address(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.address, ::std::string::String::new())
+    }
+
+    // bool in_roll_call = 2;
+
+
+    pub fn get_in_roll_call(&self) -> bool {
+        self.in_roll_call
+    }
+    pub fn clear_in_roll_call(&mut self) {
+        self.in_roll_call = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_in_roll_call(&mut self, v: bool) {
+        self.in_roll_call = v;
+    }
+
+    // bool in_election = 3;
+
+
+    pub fn get_in_election(&self) -> bool {
+        self.in_election
+    }
+    pub fn clear_in_election(&mut self) {
+        self.in_election = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_in_election(&mut self, v: bool) {
+        self.in_election = v;
+    }
+
+    // string successor = 4;
+
+
+    pub fn get_successor(&self) -> &str {
+        &self.successor
+    }
+    pub fn clear_successor(&mut self) {
+        self.successor.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_successor(&mut self, v: ::std::string::String) {
+        self.successor = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_successor(&mut self) -> &mut ::std::string::String {
+        &mut self.successor
+    }
+
+    // Take field
+    pub fn take_successor(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.successor, ::std::string::String::new())
+    }
+
+    // string leader = 5;
+
+
+    pub fn get_leader(&self) -> &str {
+        &self.leader
+    }
+    pub fn clear_leader(&mut self) {
+        self.leader.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_leader(&mut self, v: ::std::string::String) {
+        self.leader = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_leader(&mut self) -> &mut ::std::string::String {
+        &mut self.leader
+    }
+
+    // Take field
+    pub fn take_leader(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.leader, ::std::string::String::new())
+    }
+
+    // repeated .ringproto.Counter handled = 6;
+
+
+    pub fn get_handled(&self) -> &[Counter] {
+        &self.handled
+    }
+    pub fn clear_handled(&mut self) {
+        self.handled.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_handled(&mut self, v: ::protobuf::RepeatedField<Counter>) {
+        self.handled = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_handled(&mut self) -> &mut ::protobuf::RepeatedField<Counter> {
+        &mut self.handled
+    }
+
+    // Take field
+    pub fn take_handled(&mut self) -> ::protobuf::RepeatedField<Counter> {
+        ::std::mem::replace(&mut self.handled, ::protobuf::RepeatedField::new())
+    }
+
+    // string last_error = 7;
+
+
+    pub fn get_last_error(&self) -> &str {
+        &self.last_error
+    }
+    pub fn clear_last_error(&mut self) {
+        self.last_error.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_last_error(&mut self, v: ::std::string::String) {
+        self.last_error = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_last_error(&mut self) -> &mut ::std::string::String {
+        &mut self.last_error
+    }
+
+    // Take field
+    pub fn take_last_error(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.last_error, ::std::string::String::new())
+    }
+
+    // repeated string roll_calls = 8;
+
+
+    pub fn get_roll_calls(&self) -> &[::std::string::String] {
+        &self.roll_calls
+    }
+    pub fn clear_roll_calls(&mut self) {
+        self.roll_calls.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_roll_calls(&mut self, v: ::protobuf::RepeatedField<::std::string::String>) {
+        self.roll_calls = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_roll_calls(&mut self) -> &mut ::protobuf::RepeatedField<::std::string::String> {
+        &mut self.roll_calls
+    }
+
+    // Take field
+    pub fn take_roll_calls(&mut self) -> ::protobuf::RepeatedField<::std::string::String> {
+        ::std::mem::replace(&mut self.roll_calls, ::protobuf::RepeatedField::new())
+    }
+
+    // uint64 last_error_at = 9;
+
+
+    pub fn get_last_error_at(&self) -> u64 {
+        self.last_error_at
+    }
+    pub fn clear_last_error_at(&mut self) {
+        self.last_error_at = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_last_error_at(&mut self, v: u64) {
+        self.last_error_at = v;
+    }
+}
+
+impl ::protobuf::Message for NodeStatus {
+    fn is_initialized(&self) -> bool {
+        for v in &self.handled {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.address)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.in_roll_call = tmp;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.in_election = tmp;
+                },
+                4 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.successor)?;
+                },
+                5 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.leader)?;
+                },
+                6 => {
+                    ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.handled)?;
+                },
+                7 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.last_error)?;
+                },
+                8 => {
+                    ::protobuf::rt::read_repeated_string_into(wire_type, is, &mut self.roll_calls)?;
+                },
+                9 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.last_error_at = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.address.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.address);
+        }
+        if self.in_roll_call != false {
+            my_size += 2;
+        }
+        if self.in_election != false {
+            my_size += 2;
+        }
+        if !self.successor.is_empty() {
+            my_size += ::protobuf::rt::string_size(4, &self.successor);
+        }
+        if !self.leader.is_empty() {
+            my_size += ::protobuf::rt::string_size(5, &self.leader);
+        }
+        for value in &self.handled {
+            let len = value.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        };
+        if !self.last_error.is_empty() {
+            my_size += ::protobuf::rt::string_size(7, &self.last_error);
+        }
+        for value in &self.roll_calls {
+            my_size += ::protobuf::rt::string_size(8, &value);
+        };
+        if self.last_error_at != 0 {
+            my_size += ::protobuf::rt::value_size(9, self.last_error_at, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.address.is_empty() {
+            os.write_string(1, &self.address)?;
+        }
+        if self.in_roll_call != false {
+            os.write_bool(2, self.in_roll_call)?;
+        }
+        if self.in_election != false {
+            os.write_bool(3, self.in_election)?;
+        }
+        if !self.successor.is_empty() {
+            os.write_string(4, &self.successor)?;
+        }
+        if !self.leader.is_empty() {
+            os.write_string(5, &self.leader)?;
+        }
+        for v in &self.handled {
+            os.write_tag(6, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        };
+        if !self.last_error.is_empty() {
+            os.write_string(7, &self.last_error)?;
+        }
+        for v in &self.roll_calls {
+            os.write_string(8, &v)?;
+        };
+        if self.last_error_at != 0 {
+            os.write_uint64(9, self.last_error_at)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> NodeStatus {
+        NodeStatus::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "address",
+                    |m: &NodeStatus| { &m.address },
+                    |m: &mut NodeStatus| { &mut m.address },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                    "in_roll_call",
+                    |m: &NodeStatus| { &m.in_roll_call },
+                    |m: &mut NodeStatus| { &mut m.in_roll_call },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                    "in_election",
+                    |m: &NodeStatus| { &m.in_election },
+                    |m: &mut NodeStatus| { &mut m.in_election },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "successor",
+                    |m: &NodeStatus| { &m.successor },
+                    |m: &mut NodeStatus| { &mut m.successor },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "leader",
+                    |m: &NodeStatus| { &m.leader },
+                    |m: &mut NodeStatus| { &mut m.leader },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<Counter>>(
+                    "handled",
+                    |m: &NodeStatus| { &m.handled },
+                    |m: &mut NodeStatus| { &mut m.handled },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "last_error",
+                    |m: &NodeStatus| { &m.last_error },
+                    |m: &mut NodeStatus| { &mut m.last_error },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "roll_calls",
+                    |m: &NodeStatus| { &m.roll_calls },
+                    |m: &mut NodeStatus| { &mut m.roll_calls },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "last_error_at",
+                    |m: &NodeStatus| { &m.last_error_at },
+                    |m: &mut NodeStatus| { &mut m.last_error_at },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<NodeStatus>(
+                    "NodeStatus",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static NodeStatus {
+        static mut instance: ::protobuf::lazy::Lazy<NodeStatus> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const NodeStatus,
+        };
+        unsafe {
+            instance.get(NodeStatus::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for NodeStatus {
+    fn clear(&mut self) {
+        self.address.clear();
+        self.in_roll_call = false;
+        self.in_election = false;
+        self.successor.clear();
+        self.leader.clear();
+        self.handled.clear();
+        self.last_error.clear();
+        self.roll_calls.clear();
+        self.last_error_at = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for NodeStatus {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for NodeStatus {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct Event {
+    // message fields
+    pub node: ::std::string::String,
+    pub kind: ::std::string::String,
+    pub detail: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a Event {
+    fn default() -> &'a Event {
+        <Event as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl Event {
+    pub fn new() -> Event {
+        ::std::default::Default::default()
+    }
+
+    // string node = 1;
+
+
+    pub fn get_node(&self) -> &str {
+        &self.node
+    }
+    pub fn clear_node(&mut self) {
+        self.node.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_node(&mut self, v: ::std::string::String) {
+        self.node = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_node(&mut self) -> &mut ::std::string::String {
+        &mut self.node
+    }
+
+    // Take field
+    pub fn take_node(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.node, ::std::string::String::new())
+    }
+
+    // string kind = 2;
+
+
+    pub fn get_kind(&self) -> &str {
+        &self.kind
+    }
+    pub fn clear_kind(&mut self) {
+        self.kind.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_kind(&mut self, v: ::std::string::String) {
+        self.kind = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_kind(&mut self) -> &mut ::std::string::String {
+        &mut self.kind
+    }
+
+    // Take field
+    pub fn take_kind(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.kind, ::std::string::String::new())
+    }
+
+    // string detail = 3;
+
+
+    pub fn get_detail(&self) -> &str {
+        &self.detail
+    }
+    pub fn clear_detail(&mut self) {
+        self.detail.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_detail(&mut self, v: ::std::string::String) {
+        self.detail = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_detail(&mut self) -> &mut ::std::string::String {
+        &mut self.detail
+    }
+
+    // Take field
+    pub fn take_detail(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.detail, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for Event {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.node)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.kind)?;
+                },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.detail)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.node.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.node);
+        }
+        if !self.kind.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.kind);
+        }
+        if !self.detail.is_empty() {
+            my_size += ::protobuf::rt::string_size(3, &self.detail);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.node.is_empty() {
+            os.write_string(1, &self.node)?;
+        }
+        if !self.kind.is_empty() {
+            os.write_string(2, &self.kind)?;
+        }
+        if !self.detail.is_empty() {
+            os.write_string(3, &self.detail)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> Event {
+        Event::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "node",
+                    |m: &Event| { &m.node },
+                    |m: &mut Event| { &mut m.node },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "kind",
+                    |m: &Event| { &m.kind },
+                    |m: &mut Event| { &mut m.kind },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "detail",
+                    |m: &Event| { &m.detail },
+                    |m: &mut Event| { &mut m.detail },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<Event>(
+                    "Event",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static Event {
+        static mut instance: ::protobuf::lazy::Lazy<Event> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const Event,
+        };
+        unsafe {
+            instance.get(Event::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for Event {
+    fn clear(&mut self) {
+        self.node.clear();
+        self.kind.clear();
+        self.detail.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for Event {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Event {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct Token {
+    // message fields
+    pub generation: u64,
+    pub sequence: u64,
+    pub requesters: ::protobuf::RepeatedField<::std::string::String>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a Token {
+    fn default() -> &'a Token {
+        <Token as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl Token {
+    pub fn new() -> Token {
+        ::std::default::Default::default()
+    }
+
+    // uint64 generation = 1;
+
+
+    pub fn get_generation(&self) -> u64 {
+        self.generation
+    }
+    pub fn clear_generation(&mut self) {
+        self.generation = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_generation(&mut self, v: u64) {
+        self.generation = v;
+    }
+
+    // uint64 sequence = 2;
+
+
+    pub fn get_sequence(&self) -> u64 {
+        self.sequence
+    }
+    pub fn clear_sequence(&mut self) {
+        self.sequence = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_sequence(&mut self, v: u64) {
+        self.sequence = v;
+    }
+
+    // repeated string requesters = 3;
+
+
+    pub fn get_requesters(&self) -> &[::std::string::String] {
+        &self.requesters
+    }
+    pub fn clear_requesters(&mut self) {
+        self.requesters.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_requesters(&mut self, v: ::protobuf::RepeatedField<::std::string::String>) {
+        self.requesters = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_requesters(&mut self) -> &mut ::protobuf::RepeatedField<::std::string::String> {
+        &mut self.requesters
+    }
+
+    // Take field
+    pub fn take_requesters(&mut self) -> ::protobuf::RepeatedField<::std::string::String> {
+        ::std::mem::replace(&mut self.requesters, ::protobuf::RepeatedField::new())
+    }
+}
+
+impl ::protobuf::Message for Token {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.generation = tmp;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.sequence = tmp;
+                },
+                3 => {
+                    ::protobuf::rt::read_repeated_string_into(wire_type, is, &mut self.requesters)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.generation != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.generation, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.sequence != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.sequence, ::protobuf::wire_format::WireTypeVarint);
+        }
+        for value in &self.requesters {
+            my_size += ::protobuf::rt::string_size(3, &value);
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if self.generation != 0 {
+            os.write_uint64(1, self.generation)?;
+        }
+        if self.sequence != 0 {
+            os.write_uint64(2, self.sequence)?;
+        }
+        for v in &self.requesters {
+            os.write_string(3, &v)?;
+        };
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> Token {
+        Token::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "generation",
+                    |m: &Token| { &m.generation },
+                    |m: &mut Token| { &mut m.generation },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "sequence",
+                    |m: &Token| { &m.sequence },
+                    |m: &mut Token| { &mut m.sequence },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "requesters",
+                    |m: &Token| { &m.requesters },
+                    |m: &mut Token| { &mut m.requesters },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<Token>(
+                    "Token",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static Token {
+        static mut instance: ::protobuf::lazy::Lazy<Token> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const Token,
+        };
+        unsafe {
+            instance.get(Token::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for Token {
+    fn clear(&mut self) {
+        self.generation = 0;
+        self.sequence = 0;
+        self.requesters.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for Token {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Token {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct TokenRequest {
+    // message fields
+    pub requester: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a TokenRequest {
+    fn default() -> &'a TokenRequest {
+        <TokenRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl TokenRequest {
+    pub fn new() -> TokenRequest {
+        ::std::default::Default::default()
+    }
+
+    // string requester = 1;
+
+
+    pub fn get_requester(&self) -> &str {
+        &self.requester
+    }
+    pub fn clear_requester(&mut self) {
+        self.requester.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_requester(&mut self, v: ::std::string::String) {
+        self.requester = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_requester(&mut self) -> &mut ::std::string::String {
+        &mut self.requester
+    }
+
+    // Take field
+    pub fn take_requester(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.requester, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for TokenRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.requester)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.requester.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.requester);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.requester.is_empty() {
+            os.write_string(1, &self.requester)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> TokenRequest {
+        TokenRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "requester",
+                    |m: &TokenRequest| { &m.requester },
+                    |m: &mut TokenRequest| { &mut m.requester },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<TokenRequest>(
+                    "TokenRequest",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static TokenRequest {
+        static mut instance: ::protobuf::lazy::Lazy<TokenRequest> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const TokenRequest,
+        };
+        unsafe {
+            instance.get(TokenRequest::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for TokenRequest {
+    fn clear(&mut self) {
+        self.requester.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for TokenRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for TokenRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct TokenProbe {
+    // message fields
+    pub initiator: ::std::string::String,
+    pub generation: u64,
+    pub sequence: u64,
+    pub found: bool,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a TokenProbe {
+    fn default() -> &'a TokenProbe {
+        <TokenProbe as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl TokenProbe {
+    pub fn new() -> TokenProbe {
+        ::std::default::Default::default()
+    }
+
+    // string initiator = 1;
+
+
+    pub fn get_initiator(&self) -> &str {
+        &self.initiator
+    }
+    pub fn clear_initiator(&mut self) {
+        self.initiator.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_initiator(&mut self, v: ::std::string::String) {
+        self.initiator = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_initiator(&mut self) -> &mut ::std::string::String {
+        &mut self.initiator
+    }
+
+    // Take field
+    pub fn take_initiator(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.initiator, ::std::string::String::new())
+    }
+
+    // uint64 generation = 2;
+
+
+    pub fn get_generation(&self) -> u64 {
+        self.generation
+    }
+    pub fn clear_generation(&mut self) {
+        self.generation = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_generation(&mut self, v: u64) {
+        self.generation = v;
+    }
+
+    // uint64 sequence = 3;
+
+
+    pub fn get_sequence(&self) -> u64 {
+        self.sequence
+    }
+    pub fn clear_sequence(&mut self) {
+        self.sequence = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_sequence(&mut self, v: u64) {
+        self.sequence = v;
+    }
+
+    // bool found = 4;
+
+
+    pub fn get_found(&self) -> bool {
+        self.found
+    }
+    pub fn clear_found(&mut self) {
+        self.found = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_found(&mut self, v: bool) {
+        self.found = v;
+    }
+}
+
+impl ::protobuf::Message for TokenProbe {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.initiator)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.generation = tmp;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.sequence = tmp;
+                },
+                4 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.found = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.initiator.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.initiator);
+        }
+        if self.generation != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.generation, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.sequence != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.sequence, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.found != false {
+            my_size += 2;
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.initiator.is_empty() {
+            os.write_string(1, &self.initiator)?;
+        }
+        if self.generation != 0 {
+            os.write_uint64(2, self.generation)?;
+        }
+        if self.sequence != 0 {
+            os.write_uint64(3, self.sequence)?;
+        }
+        if self.found != false {
+            os.write_bool(4, self.found)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> TokenProbe {
+        TokenProbe::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "initiator",
+                    |m: &TokenProbe| { &m.initiator },
+                    |m: &mut TokenProbe| { &mut m.initiator },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "generation",
+                    |m: &TokenProbe| { &m.generation },
+                    |m: &mut TokenProbe| { &mut m.generation },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "sequence",
+                    |m: &TokenProbe| { &m.sequence },
+                    |m: &mut TokenProbe| { &mut m.sequence },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                    "found",
+                    |m: &TokenProbe| { &m.found },
+                    |m: &mut TokenProbe| { &mut m.found },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<TokenProbe>(
+                    "TokenProbe",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static TokenProbe {
+        static mut instance: ::protobuf::lazy::Lazy<TokenProbe> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const TokenProbe,
+        };
+        unsafe {
+            instance.get(TokenProbe::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for TokenProbe {
+    fn clear(&mut self) {
+        self.initiator.clear();
+        self.generation = 0;
+        self.sequence = 0;
+        self.found = false;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for TokenProbe {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for TokenProbe {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct Lock {
+    // message fields
+    pub id: ::std::string::String,
+    pub lease_secs: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a Lock {
+    fn default() -> &'a Lock {
+        <Lock as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl Lock {
+    pub fn new() -> Lock {
+        ::std::default::Default::default()
+    }
+
+    // string id = 1;
+
+
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+    pub fn clear_id(&mut self) {
+        self.id.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_id(&mut self, v: ::std::string::String) {
+        self.id = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_id(&mut self) -> &mut ::std::string::String {
+        &mut self.id
+    }
+
+    // Take field
+    pub fn take_id(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.id, ::std::string::String::new())
+    }
+
+    // uint64 lease_secs = 2;
+
+
+    pub fn get_lease_secs(&self) -> u64 {
+        self.lease_secs
+    }
+    pub fn clear_lease_secs(&mut self) {
+        self.lease_secs = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_lease_secs(&mut self, v: u64) {
+        self.lease_secs = v;
+    }
+}
+
+impl ::protobuf::Message for Lock {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.id)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.lease_secs = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.id.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.id);
+        }
+        if self.lease_secs != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.lease_secs, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.id.is_empty() {
+            os.write_string(1, &self.id)?;
+        }
+        if self.lease_secs != 0 {
+            os.write_uint64(2, self.lease_secs)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> Lock {
+        Lock::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "id",
+                    |m: &Lock| { &m.id },
+                    |m: &mut Lock| { &mut m.id },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "lease_secs",
+                    |m: &Lock| { &m.lease_secs },
+                    |m: &mut Lock| { &mut m.lease_secs },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<Lock>(
+                    "Lock",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static Lock {
+        static mut instance: ::protobuf::lazy::Lazy<Lock> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const Lock,
+        };
+        unsafe {
+            instance.get(Lock::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for Lock {
+    fn clear(&mut self) {
+        self.id.clear();
+        self.lease_secs = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for Lock {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Lock {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
 static file_descriptor_proto_data: &'static [u8] = b"\
-    \n\nring.proto\x12\tringproto\"\x07\n\x05Empty2m\n\x04Ring\x123\n\rStart\
-    RollCall\x12\x10.ringproto.Empty\x1a\x10.ringproto.Empty\x120\n\nMarkIts\
-    elf\x12\x10.ringproto.Empty\x1a\x10.ringproto.Emptyb\x06proto3\
+    \n\nring.proto\x12\tringproto\"\x07\n\x05Empty\"P\n\x08RollCall\x12\x0e\
+    \n\x02id\x18\x01\x20\x01(\tR\x02id\x12\x1c\n\tinitiator\x18\x02\x20\x01(\
+    \tR\tinitiator\x12\x16\n\x06marked\x18\x03\x20\x03(\tR\x06marked\"/\n\
+    \x05Tally\x12\x0e\n\x02id\x18\x01\x20\x01(\tR\x02id\x12\x16\n\x06marked\
+    \x18\x02\x20\x03(\tR\x06marked\"'\n\x0bJoinRequest\x12\x18\n\x07address\
+    \x18\x01\x20\x01(\tR\x07address\")\n\tJoinReply\x12\x1c\n\tsuccessor\x18\
+    \x01\x20\x01(\tR\tsuccessor\"^\n\x0cLeaveRequest\x12\x18\n\x07address\
+    \x18\x01\x20\x01(\tR\x07address\x12\x1c\n\tsuccessor\x18\x02\x20\x01(\tR\
+    \tsuccessor\x12\x16\n\x06origin\x18\x03\x20\x01(\tR\x06origin\"*\n\nSucc\
+    essors\x12\x1c\n\taddresses\x18\x01\x20\x03(\tR\taddresses\"5\n\tCandida\
+    te\x12\x0e\n\x02id\x18\x01\x20\x01(\x04R\x02id\x12\x18\n\x07address\x18\
+    \x02\x20\x01(\tR\x07address\"7\n\x07Counter\x12\x16\n\x06action\x18\x01\
+    \x20\x01(\tR\x06action\x12\x14\n\x05count\x18\x02\x20\x01(\x04R\x05count\
+    \"\xaf\x02\n\nNodeStatus\x12\x18\n\x07address\x18\x01\x20\x01(\tR\x07add\
+    ress\x12\x20\n\x0cin_roll_call\x18\x02\x20\x01(\x08R\ninRollCall\x12\x1f\
+    \n\x0bin_election\x18\x03\x20\x01(\x08R\ninElection\x12\x1c\n\tsuccessor\
+    \x18\x04\x20\x01(\tR\tsuccessor\x12\x16\n\x06leader\x18\x05\x20\x01(\tR\
+    \x06leader\x12,\n\x07handled\x18\x06\x20\x03(\x0b2\x12.ringproto.Counter\
+    R\x07handled\x12\x1d\n\nlast_error\x18\x07\x20\x01(\tR\tlastError\x12\
+    \x1d\n\nroll_calls\x18\x08\x20\x03(\tR\trollCalls\x12\"\n\rlast_error_at\
+    \x18\t\x20\x01(\x04R\x0blastErrorAt\"G\n\x05Event\x12\x12\n\x04node\x18\
+    \x01\x20\x01(\tR\x04node\x12\x12\n\x04kind\x18\x02\x20\x01(\tR\x04kind\
+    \x12\x16\n\x06detail\x18\x03\x20\x01(\tR\x06detail\"c\n\x05Token\x12\x1e\
+    \n\ngeneration\x18\x01\x20\x01(\x04R\ngeneration\x12\x1a\n\x08sequence\
+    \x18\x02\x20\x01(\x04R\x08sequence\x12\x1e\n\nrequesters\x18\x03\x20\x03\
+    (\tR\nrequesters\",\n\x0cTokenRequest\x12\x1c\n\trequester\x18\x01\x20\
+    \x01(\tR\trequester\"|\n\nTokenProbe\x12\x1c\n\tinitiator\x18\x01\x20\
+    \x01(\tR\tinitiator\x12\x1e\n\ngeneration\x18\x02\x20\x01(\x04R\ngenerat\
+    ion\x12\x1a\n\x08sequence\x18\x03\x20\x01(\x04R\x08sequence\x12\x14\n\
+    \x05found\x18\x04\x20\x01(\x08R\x05found\"5\n\x04Lock\x12\x0e\n\x02id\
+    \x18\x01\x20\x01(\tR\x02id\x12\x1d\n\nlease_secs\x18\x02\x20\x01(\x04R\t\
+    leaseSecs2\xcb\x06\n\x04Ring\x129\n\rStartRollCall\x12\x13.ringproto.Rol\
+    lCall\x1a\x13.ringproto.RollCall\x123\n\nMarkItself\x12\x13.ringproto.Ro\
+    llCall\x1a\x10.ringproto.Empty\x123\n\nAwaitTally\x12\x13.ringproto.Roll\
+    Call\x1a\x10.ringproto.Tally\x124\n\x04Join\x12\x16.ringproto.JoinReques\
+    t\x1a\x14.ringproto.JoinReply\x122\n\x05Leave\x12\x17.ringproto.LeaveReq\
+    uest\x1a\x10.ringproto.Empty\x124\n\tHeartbeat\x12\x10.ringproto.Empty\
+    \x1a\x15.ringproto.Successors\x12/\n\x05Elect\x12\x14.ringproto.Candidat\
+    e\x1a\x10.ringproto.Empty\x121\n\x07Elected\x12\x14.ringproto.Candidate\
+    \x1a\x10.ringproto.Empty\x123\n\tGetLeader\x12\x10.ringproto.Empty\x1a\
+    \x14.ringproto.Candidate\x121\n\x06Status\x12\x10.ringproto.Empty\x1a\
+    \x15.ringproto.NodeStatus\x123\n\x0bWatchEvents\x12\x10.ringproto.Empty\
+    \x1a\x10.ringproto.Event0\x01\x12/\n\tPassToken\x12\x10.ringproto.Token\
+    \x1a\x10.ringproto.Empty\x125\n\nProbeToken\x12\x15.ringproto.TokenProbe\
+    \x1a\x10.ringproto.Empty\x129\n\x0cRequestToken\x12\x17.ringproto.TokenR\
+    equest\x1a\x10.ringproto.Empty\x12,\n\x07Acquire\x12\x10.ringproto.Empty\
+    \x1a\x0f.ringproto.Lock\x12,\n\x07Release\x12\x0f.ringproto.Lock\x1a\x10\
+    .ringproto.Emptyb\x06proto3\
 ";
 
 static mut file_descriptor_proto_lazy: ::protobuf::lazy::Lazy<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::lazy::Lazy {
@@ -22,17 +22,59 @@
 // interface
 
 pub trait Ring {
-    fn start_roll_call(&self, o: ::grpc::RequestOptions, p: super::ring::Empty) -> ::grpc::SingleResponse<super::ring::Empty>;
+    fn start_roll_call(&self, o: ::grpc::RequestOptions, p: super::ring::RollCall) -> ::grpc::SingleResponse<super::ring::RollCall>;
 
-    fn mark_itself(&self, o: ::grpc::RequestOptions, p: super::ring::Empty) -> ::grpc::SingleResponse<super::ring::Empty>;
+    fn mark_itself(&self, o: ::grpc::RequestOptions, p: super::ring::RollCall) -> ::grpc::SingleResponse<super::ring::Empty>;
+
+    fn await_tally(&self, o: ::grpc::RequestOptions, p: super::ring::RollCall) -> ::grpc::SingleResponse<super::ring::Tally>;
+
+    fn join(&self, o: ::grpc::RequestOptions, p: super::ring::JoinRequest) -> ::grpc::SingleResponse<super::ring::JoinReply>;
+
+    fn leave(&self, o: ::grpc::RequestOptions, p: super::ring::LeaveRequest) -> ::grpc::SingleResponse<super::ring::Empty>;
+
+    fn heartbeat(&self, o: ::grpc::RequestOptions, p: super::ring::Empty) -> ::grpc::SingleResponse<super::ring::Successors>;
+
+    fn elect(&self, o: ::grpc::RequestOptions, p: super::ring::Candidate) -> ::grpc::SingleResponse<super::ring::Empty>;
+
+    fn elected(&self, o: ::grpc::RequestOptions, p: super::ring::Candidate) -> ::grpc::SingleResponse<super::ring::Empty>;
+
+    fn get_leader(&self, o: ::grpc::RequestOptions, p: super::ring::Empty) -> ::grpc::SingleResponse<super::ring::Candidate>;
+
+    fn status(&self, o: ::grpc::RequestOptions, p: super::ring::Empty) -> ::grpc::SingleResponse<super::ring::NodeStatus>;
+
+    fn watch_events(&self, o: ::grpc::RequestOptions, p: super::ring::Empty) -> ::grpc::StreamingResponse<super::ring::Event>;
+
+    fn pass_token(&self, o: ::grpc::RequestOptions, p: super::ring::Token) -> ::grpc::SingleResponse<super::ring::Empty>;
+
+    fn probe_token(&self, o: ::grpc::RequestOptions, p: super::ring::TokenProbe) -> ::grpc::SingleResponse<super::ring::Empty>;
+
+    fn request_token(&self, o: ::grpc::RequestOptions, p: super::ring::TokenRequest) -> ::grpc::SingleResponse<super::ring::Empty>;
+
+    fn acquire(&self, o: ::grpc::RequestOptions, p: super::ring::Empty) -> ::grpc::SingleResponse<super::ring::Lock>;
+
+    fn release(&self, o: ::grpc::RequestOptions, p: super::ring::Lock) -> ::grpc::SingleResponse<super::ring::Empty>;
 }
 
 // client
 
 pub struct RingClient {
     grpc_client: ::std::sync::Arc<::grpc::Client>,
-    method_StartRollCall: ::std::sync::Arc<::grpc::rt::MethodDescriptor<super::ring::Empty, super::ring::Empty>>,
-    method_MarkItself: ::std::sync::Arc<::grpc::rt::MethodDescriptor<super::ring::Empty, super::ring::Empty>>,
+    method_StartRollCall: ::std::sync::Arc<::grpc::rt::MethodDescriptor<super::ring::RollCall, super::ring::RollCall>>,
+    method_MarkItself: ::std::sync::Arc<::grpc::rt::MethodDescriptor<super::ring::RollCall, super::ring::Empty>>,
+    method_AwaitTally: ::std::sync::Arc<::grpc::rt::MethodDescriptor<super::ring::RollCall, super::ring::Tally>>,
+    method_Join: ::std::sync::Arc<::grpc::rt::MethodDescriptor<super::ring::JoinRequest, super::ring::JoinReply>>,
+    method_Leave: ::std::sync::Arc<::grpc::rt::MethodDescriptor<super::ring::LeaveRequest, super::ring::Empty>>,
+    method_Heartbeat: ::std::sync::Arc<::grpc::rt::MethodDescriptor<super::ring::Empty, super::ring::Successors>>,
+    method_Elect: ::std::sync::Arc<::grpc::rt::MethodDescriptor<super::ring::Candidate, super::ring::Empty>>,
+    method_Elected: ::std::sync::Arc<::grpc::rt::MethodDescriptor<super::ring::Candidate, super::ring::Empty>>,
+    method_GetLeader: ::std::sync::Arc<::grpc::rt::MethodDescriptor<super::ring::Empty, super::ring::Candidate>>,
+    method_Status: ::std::sync::Arc<::grpc::rt::MethodDescriptor<super::ring::Empty, super::ring::NodeStatus>>,
+    method_WatchEvents: ::std::sync::Arc<::grpc::rt::MethodDescriptor<super::ring::Empty, super::ring::Event>>,
+    method_PassToken: ::std::sync::Arc<::grpc::rt::MethodDescriptor<super::ring::Token, super::ring::Empty>>,
+    method_ProbeToken: ::std::sync::Arc<::grpc::rt::MethodDescriptor<super::ring::TokenProbe, super::ring::Empty>>,
+    method_RequestToken: ::std::sync::Arc<::grpc::rt::MethodDescriptor<super::ring::TokenRequest, super::ring::Empty>>,
+    method_Acquire: ::std::sync::Arc<::grpc::rt::MethodDescriptor<super::ring::Empty, super::ring::Lock>>,
+    method_Release: ::std::sync::Arc<::grpc::rt::MethodDescriptor<super::ring::Lock, super::ring::Empty>>,
 }
 
 impl ::grpc::ClientStub for RingClient {
@@ -51,18 +93,158 @@ impl ::grpc::ClientStub for RingClient {
                 req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
                 resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
             }),
+            method_AwaitTally: ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                name: "/ringproto.Ring/AwaitTally".to_string(),
+                streaming: ::grpc::rt::GrpcStreaming::Unary,
+                req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+            }),
+            method_Join: ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                name: "/ringproto.Ring/Join".to_string(),
+                streaming: ::grpc::rt::GrpcStreaming::Unary,
+                req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+            }),
+            method_Leave: ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                name: "/ringproto.Ring/Leave".to_string(),
+                streaming: ::grpc::rt::GrpcStreaming::Unary,
+                req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+            }),
+            method_Heartbeat: ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                name: "/ringproto.Ring/Heartbeat".to_string(),
+                streaming: ::grpc::rt::GrpcStreaming::Unary,
+                req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+            }),
+            method_Elect: ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                name: "/ringproto.Ring/Elect".to_string(),
+                streaming: ::grpc::rt::GrpcStreaming::Unary,
+                req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+            }),
+            method_Elected: ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                name: "/ringproto.Ring/Elected".to_string(),
+                streaming: ::grpc::rt::GrpcStreaming::Unary,
+                req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+            }),
+            method_GetLeader: ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                name: "/ringproto.Ring/GetLeader".to_string(),
+                streaming: ::grpc::rt::GrpcStreaming::Unary,
+                req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+            }),
+            method_Status: ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                name: "/ringproto.Ring/Status".to_string(),
+                streaming: ::grpc::rt::GrpcStreaming::Unary,
+                req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+            }),
+            method_WatchEvents: ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                name: "/ringproto.Ring/WatchEvents".to_string(),
+                streaming: ::grpc::rt::GrpcStreaming::ServerStreaming,
+                req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+            }),
+            method_PassToken: ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                name: "/ringproto.Ring/PassToken".to_string(),
+                streaming: ::grpc::rt::GrpcStreaming::Unary,
+                req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+            }),
+            method_ProbeToken: ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                name: "/ringproto.Ring/ProbeToken".to_string(),
+                streaming: ::grpc::rt::GrpcStreaming::Unary,
+                req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+            }),
+            method_RequestToken: ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                name: "/ringproto.Ring/RequestToken".to_string(),
+                streaming: ::grpc::rt::GrpcStreaming::Unary,
+                req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+            }),
+            method_Acquire: ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                name: "/ringproto.Ring/Acquire".to_string(),
+                streaming: ::grpc::rt::GrpcStreaming::Unary,
+                req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+            }),
+            method_Release: ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                name: "/ringproto.Ring/Release".to_string(),
+                streaming: ::grpc::rt::GrpcStreaming::Unary,
+                req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+            }),
         }
     }
 }
 
 impl Ring for RingClient {
-    fn start_roll_call(&self, o: ::grpc::RequestOptions, p: super::ring::Empty) -> ::grpc::SingleResponse<super::ring::Empty> {
+    fn start_roll_call(&self, o: ::grpc::RequestOptions, p: super::ring::RollCall) -> ::grpc::SingleResponse<super::ring::RollCall> {
         self.grpc_client.call_unary(o, p, self.method_StartRollCall.clone())
     }
 
-    fn mark_itself(&self, o: ::grpc::RequestOptions, p: super::ring::Empty) -> ::grpc::SingleResponse<super::ring::Empty> {
+    fn mark_itself(&self, o: ::grpc::RequestOptions, p: super::ring::RollCall) -> ::grpc::SingleResponse<super::ring::Empty> {
         self.grpc_client.call_unary(o, p, self.method_MarkItself.clone())
     }
+
+    fn await_tally(&self, o: ::grpc::RequestOptions, p: super::ring::RollCall) -> ::grpc::SingleResponse<super::ring::Tally> {
+        self.grpc_client.call_unary(o, p, self.method_AwaitTally.clone())
+    }
+
+    fn join(&self, o: ::grpc::RequestOptions, p: super::ring::JoinRequest) -> ::grpc::SingleResponse<super::ring::JoinReply> {
+        self.grpc_client.call_unary(o, p, self.method_Join.clone())
+    }
+
+    fn leave(&self, o: ::grpc::RequestOptions, p: super::ring::LeaveRequest) -> ::grpc::SingleResponse<super::ring::Empty> {
+        self.grpc_client.call_unary(o, p, self.method_Leave.clone())
+    }
+
+    fn heartbeat(&self, o: ::grpc::RequestOptions, p: super::ring::Empty) -> ::grpc::SingleResponse<super::ring::Successors> {
+        self.grpc_client.call_unary(o, p, self.method_Heartbeat.clone())
+    }
+
+    fn elect(&self, o: ::grpc::RequestOptions, p: super::ring::Candidate) -> ::grpc::SingleResponse<super::ring::Empty> {
+        self.grpc_client.call_unary(o, p, self.method_Elect.clone())
+    }
+
+    fn elected(&self, o: ::grpc::RequestOptions, p: super::ring::Candidate) -> ::grpc::SingleResponse<super::ring::Empty> {
+        self.grpc_client.call_unary(o, p, self.method_Elected.clone())
+    }
+
+    fn get_leader(&self, o: ::grpc::RequestOptions, p: super::ring::Empty) -> ::grpc::SingleResponse<super::ring::Candidate> {
+        self.grpc_client.call_unary(o, p, self.method_GetLeader.clone())
+    }
+
+    fn status(&self, o: ::grpc::RequestOptions, p: super::ring::Empty) -> ::grpc::SingleResponse<super::ring::NodeStatus> {
+        self.grpc_client.call_unary(o, p, self.method_Status.clone())
+    }
+
+    fn watch_events(&self, o: ::grpc::RequestOptions, p: super::ring::Empty) -> ::grpc::StreamingResponse<super::ring::Event> {
+        self.grpc_client.call_server_streaming(o, p, self.method_WatchEvents.clone())
+    }
+
+    fn pass_token(&self, o: ::grpc::RequestOptions, p: super::ring::Token) -> ::grpc::SingleResponse<super::ring::Empty> {
+        self.grpc_client.call_unary(o, p, self.method_PassToken.clone())
+    }
+
+    fn probe_token(&self, o: ::grpc::RequestOptions, p: super::ring::TokenProbe) -> ::grpc::SingleResponse<super::ring::Empty> {
+        self.grpc_client.call_unary(o, p, self.method_ProbeToken.clone())
+    }
+
+    fn request_token(&self, o: ::grpc::RequestOptions, p: super::ring::TokenRequest) -> ::grpc::SingleResponse<super::ring::Empty> {
+        self.grpc_client.call_unary(o, p, self.method_RequestToken.clone())
+    }
+
+    fn acquire(&self, o: ::grpc::RequestOptions, p: super::ring::Empty) -> ::grpc::SingleResponse<super::ring::Lock> {
+        self.grpc_client.call_unary(o, p, self.method_Acquire.clone())
+    }
+
+    fn release(&self, o: ::grpc::RequestOptions, p: super::ring::Lock) -> ::grpc::SingleResponse<super::ring::Empty> {
+        self.grpc_client.call_unary(o, p, self.method_Release.clone())
+    }
 }
 
 // server
@@ -99,6 +281,174 @@ impl RingServer {
                         ::grpc::rt::MethodHandlerUnary::new(move |o, p| handler_copy.mark_itself(o, p))
                     },
                 ),
+                ::grpc::rt::ServerMethod::new(
+                    ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                        name: "/ringproto.Ring/AwaitTally".to_string(),
+                        streaming: ::grpc::rt::GrpcStreaming::Unary,
+                        req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                        resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                    }),
+                    {
+                        let handler_copy = handler_arc.clone();
+                        ::grpc::rt::MethodHandlerUnary::new(move |o, p| handler_copy.await_tally(o, p))
+                    },
+                ),
+                ::grpc::rt::ServerMethod::new(
+                    ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                        name: "/ringproto.Ring/Join".to_string(),
+                        streaming: ::grpc::rt::GrpcStreaming::Unary,
+                        req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                        resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                    }),
+                    {
+                        let handler_copy = handler_arc.clone();
+                        ::grpc::rt::MethodHandlerUnary::new(move |o, p| handler_copy.join(o, p))
+                    },
+                ),
+                ::grpc::rt::ServerMethod::new(
+                    ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                        name: "/ringproto.Ring/Leave".to_string(),
+                        streaming: ::grpc::rt::GrpcStreaming::Unary,
+                        req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                        resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                    }),
+                    {
+                        let handler_copy = handler_arc.clone();
+                        ::grpc::rt::MethodHandlerUnary::new(move |o, p| handler_copy.leave(o, p))
+                    },
+                ),
+                ::grpc::rt::ServerMethod::new(
+                    ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                        name: "/ringproto.Ring/Heartbeat".to_string(),
+                        streaming: ::grpc::rt::GrpcStreaming::Unary,
+                        req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                        resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                    }),
+                    {
+                        let handler_copy = handler_arc.clone();
+                        ::grpc::rt::MethodHandlerUnary::new(move |o, p| handler_copy.heartbeat(o, p))
+                    },
+                ),
+                ::grpc::rt::ServerMethod::new(
+                    ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                        name: "/ringproto.Ring/Elect".to_string(),
+                        streaming: ::grpc::rt::GrpcStreaming::Unary,
+                        req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                        resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                    }),
+                    {
+                        let handler_copy = handler_arc.clone();
+                        ::grpc::rt::MethodHandlerUnary::new(move |o, p| handler_copy.elect(o, p))
+                    },
+                ),
+                ::grpc::rt::ServerMethod::new(
+                    ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                        name: "/ringproto.Ring/Elected".to_string(),
+                        streaming: ::grpc::rt::GrpcStreaming::Unary,
+                        req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                        resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                    }),
+                    {
+                        let handler_copy = handler_arc.clone();
+                        ::grpc::rt::MethodHandlerUnary::new(move |o, p| handler_copy.elected(o, p))
+                    },
+                ),
+                ::grpc::rt::ServerMethod::new(
+                    ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                        name: "/ringproto.Ring/GetLeader".to_string(),
+                        streaming: ::grpc::rt::GrpcStreaming::Unary,
+                        req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                        resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                    }),
+                    {
+                        let handler_copy = handler_arc.clone();
+                        ::grpc::rt::MethodHandlerUnary::new(move |o, p| handler_copy.get_leader(o, p))
+                    },
+                ),
+                ::grpc::rt::ServerMethod::new(
+                    ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                        name: "/ringproto.Ring/Status".to_string(),
+                        streaming: ::grpc::rt::GrpcStreaming::Unary,
+                        req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                        resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                    }),
+                    {
+                        let handler_copy = handler_arc.clone();
+                        ::grpc::rt::MethodHandlerUnary::new(move |o, p| handler_copy.status(o, p))
+                    },
+                ),
+                ::grpc::rt::ServerMethod::new(
+                    ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                        name: "/ringproto.Ring/WatchEvents".to_string(),
+                        streaming: ::grpc::rt::GrpcStreaming::ServerStreaming,
+                        req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                        resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                    }),
+                    {
+                        let handler_copy = handler_arc.clone();
+                        ::grpc::rt::MethodHandlerServerStreaming::new(move |o, p| handler_copy.watch_events(o, p))
+                    },
+                ),
+                ::grpc::rt::ServerMethod::new(
+                    ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                        name: "/ringproto.Ring/PassToken".to_string(),
+                        streaming: ::grpc::rt::GrpcStreaming::Unary,
+                        req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                        resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                    }),
+                    {
+                        let handler_copy = handler_arc.clone();
+                        ::grpc::rt::MethodHandlerUnary::new(move |o, p| handler_copy.pass_token(o, p))
+                    },
+                ),
+                ::grpc::rt::ServerMethod::new(
+                    ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                        name: "/ringproto.Ring/ProbeToken".to_string(),
+                        streaming: ::grpc::rt::GrpcStreaming::Unary,
+                        req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                        resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                    }),
+                    {
+                        let handler_copy = handler_arc.clone();
+                        ::grpc::rt::MethodHandlerUnary::new(move |o, p| handler_copy.probe_token(o, p))
+                    },
+                ),
+                ::grpc::rt::ServerMethod::new(
+                    ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                        name: "/ringproto.Ring/RequestToken".to_string(),
+                        streaming: ::grpc::rt::GrpcStreaming::Unary,
+                        req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                        resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                    }),
+                    {
+                        let handler_copy = handler_arc.clone();
+                        ::grpc::rt::MethodHandlerUnary::new(move |o, p| handler_copy.request_token(o, p))
+                    },
+                ),
+                ::grpc::rt::ServerMethod::new(
+                    ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                        name: "/ringproto.Ring/Acquire".to_string(),
+                        streaming: ::grpc::rt::GrpcStreaming::Unary,
+                        req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                        resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                    }),
+                    {
+                        let handler_copy = handler_arc.clone();
+                        ::grpc::rt::MethodHandlerUnary::new(move |o, p| handler_copy.acquire(o, p))
+                    },
+                ),
+                ::grpc::rt::ServerMethod::new(
+                    ::std::sync::Arc::new(::grpc::rt::MethodDescriptor {
+                        name: "/ringproto.Ring/Release".to_string(),
+                        streaming: ::grpc::rt::GrpcStreaming::Unary,
+                        req_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                        resp_marshaller: Box::new(::grpc::protobuf::MarshallerProtobuf),
+                    }),
+                    {
+                        let handler_copy = handler_arc.clone();
+                        ::grpc::rt::MethodHandlerUnary::new(move |o, p| handler_copy.release(o, p))
+                    },
+                ),
             ],
         )
     }
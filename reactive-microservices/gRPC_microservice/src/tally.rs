@@ -0,0 +1,104 @@
+use crate::ring::Tally;
+
+use futures::sync::oneshot;
+use futures::Future;
+use grpc::{Error as GrpcError, GrpcMessageError, SingleResponse};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Completed tallies kept for late `AwaitTally` calls, the oldest are dropped first
+const MAX_TALLIES: usize = 100;
+/// A roll call whose tally hasn't come back after this long is given up, it was lost with a
+/// node on the way
+const TALLY_TIMEOUT: Duration = Duration::from_secs(60);
+/// `grpc-status` of a call that ran out of time
+const DEADLINE_EXCEEDED: i32 = 4;
+
+type Waiter = oneshot::Sender<Result<Tally, GrpcError>>;
+
+enum State {
+    Pending(Instant, Vec<Waiter>),
+    Done(Tally),
+    Expired,
+}
+
+/// The roll calls a node initiated, by id
+#[derive(Default)]
+pub struct Tallies {
+    states: Mutex<(HashMap<String, State>, VecDeque<String>)>,
+}
+
+impl Tallies {
+    pub fn start(&self, id: String) {
+        let mut guard = self.states.lock().unwrap();
+        let (ref mut states, ref mut order) = *guard;
+        states.insert(id.clone(), State::Pending(Instant::now(), Vec::new()));
+        order.push_back(id);
+        while order.len() > MAX_TALLIES {
+            if let Some(oldest) = order.pop_front() {
+                states.remove(&oldest);
+            }
+        }
+    }
+
+    /// Completes the roll call, even after it was given up
+    pub fn complete(&self, tally: Tally) {
+        let mut guard = self.states.lock().unwrap();
+        let state = match guard.0.get_mut(tally.get_id()) {
+            Some(state) => state,
+            None => return,
+        };
+        if let State::Pending(_, ref mut waiters) = *state {
+            for waiter in waiters.drain(..) {
+                let _ = waiter.send(Ok(tally.clone()));
+            }
+        }
+        *state = State::Done(tally);
+    }
+
+    /// Gives up the roll calls pending for longer than `TALLY_TIMEOUT`, their callers get
+    /// DEADLINE_EXCEEDED
+    pub fn expire(&self) {
+        let mut guard = self.states.lock().unwrap();
+        for state in guard.0.values_mut() {
+            let expired = match *state {
+                State::Pending(started, ref mut waiters) if started.elapsed() >= TALLY_TIMEOUT => {
+                    for waiter in waiters.drain(..) {
+                        let _ = waiter.send(Err(deadline_exceeded()));
+                    }
+                    true
+                }
+                _ => false,
+            };
+            if expired {
+                *state = State::Expired;
+            }
+        }
+    }
+
+    /// Answers once the roll call `id` is complete, at once if it already is
+    pub fn wait(&self, id: &str) -> SingleResponse<Tally> {
+        let mut guard = self.states.lock().unwrap();
+        match guard.0.get_mut(id) {
+            Some(State::Done(tally)) => SingleResponse::completed(tally.clone()),
+            Some(State::Pending(_, waiters)) => {
+                let (tx, rx) = oneshot::channel();
+                waiters.push(tx);
+                let tally = rx
+                    .map_err(|_| GrpcError::Other("the tally was dropped"))
+                    .and_then(|result| result);
+                SingleResponse::no_metadata(tally)
+            }
+            Some(State::Expired) => SingleResponse::err(deadline_exceeded()),
+            None => SingleResponse::err(GrpcError::Other("unknown roll call")),
+        }
+    }
+}
+
+fn deadline_exceeded() -> GrpcError {
+    GrpcError::GrpcMessage(GrpcMessageError {
+        grpc_status: DEADLINE_EXCEEDED,
+        grpc_message: "no tally came back".to_owned(),
+    })
+}
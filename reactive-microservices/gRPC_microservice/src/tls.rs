@@ -0,0 +1,84 @@
+use failure::Error;
+use openssl::ssl::{SslAcceptor, SslFiletype, SslMethod, SslVerifyMode};
+use openssl::x509::store::X509StoreBuilder;
+use openssl::x509::X509;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tls_api::{TlsAcceptorBuilder as _, TlsConnector as _, TlsConnectorBuilder as _};
+use tls_api_openssl::{TlsAcceptor, TlsAcceptorBuilder, TlsConnector};
+
+/// Mutual TLS between nodes and clients, every file is PEM. Both sides present `cert` and
+/// only accept a peer whose certificate `ca` signed
+#[derive(Clone, Debug)]
+pub struct TlsConfig {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+    pub ca: PathBuf,
+    /// The name server certificates must carry, the server's IP address if not set
+    pub domain: Option<String>,
+}
+
+impl TlsConfig {
+    /// From `TLS_CERT`, `TLS_KEY`, `TLS_CA` and `TLS_DOMAIN`, without any of the files
+    /// connections are plain
+    pub fn from_env() -> Result<Option<Self>, Error> {
+        let files = (
+            env::var("TLS_CERT"),
+            env::var("TLS_KEY"),
+            env::var("TLS_CA"),
+        );
+        match files {
+            (Ok(cert), Ok(key), Ok(ca)) => Ok(Some(Self {
+                cert: cert.into(),
+                key: key.into(),
+                ca: ca.into(),
+                domain: env::var("TLS_DOMAIN").ok(),
+            })),
+            (Err(_), Err(_), Err(_)) => Ok(None),
+            _ => Err(failure::err_msg(
+                "TLS needs all of TLS_CERT, TLS_KEY and TLS_CA",
+            )),
+        }
+    }
+
+    /// For the server, it refuses clients without a certificate signed by the CA
+    pub fn acceptor(&self) -> Result<TlsAcceptor, Error> {
+        let mut acceptor = SslAcceptor::mozilla_intermediate(SslMethod::tls())?;
+        acceptor.set_private_key_file(&self.key, SslFiletype::PEM)?;
+        acceptor.set_certificate_chain_file(&self.cert)?;
+        acceptor.check_private_key()?;
+        acceptor.set_ca_file(&self.ca)?;
+        acceptor.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+        Ok(TlsAcceptorBuilder(acceptor).build()?)
+    }
+
+    /// For clients, it trusts the CA and nothing else
+    pub fn client(&self) -> Result<ClientTls, Error> {
+        let ca = X509::from_pem(&fs::read(&self.ca)?)?;
+        let mut store = X509StoreBuilder::new()?;
+        store.add_cert(ca)?;
+
+        let mut builder = TlsConnector::builder()?;
+        {
+            let connector = builder.underlying_mut();
+            connector.set_cert_store(store.build());
+            connector.set_private_key_file(&self.key, SslFiletype::PEM)?;
+            connector.set_certificate_chain_file(&self.cert)?;
+            connector.check_private_key()?;
+        }
+
+        Ok(ClientTls {
+            connector: Arc::new(builder.build()?),
+            domain: self.domain.clone(),
+        })
+    }
+}
+
+/// A client's side of the TLS configuration, built once and shared by its connections
+#[derive(Clone)]
+pub struct ClientTls {
+    pub(crate) connector: Arc<TlsConnector>,
+    pub(crate) domain: Option<String>,
+}
@@ -0,0 +1,115 @@
+use futures::Stream;
+use grpc_microservice::cluster::{Cluster, Fault};
+use std::net::SocketAddr;
+use std::thread;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+const DEADLINE: Duration = Duration::from_secs(10);
+/// Enough for a hung node to miss the heartbeats that get it dropped
+const HUNG_DEADLINE: Duration = Duration::from_secs(30);
+
+/// Runs a roll call from the node at `index` and checks it marked exactly the running nodes,
+/// each of which is off again
+fn assert_roll_call(cluster: &mut Cluster, index: usize) {
+    let tally = cluster.roll_call(index, DEADLINE).unwrap();
+    let mut marked = tally
+        .get_marked()
+        .iter()
+        .map(|node| node.parse().unwrap())
+        .collect::<Vec<SocketAddr>>();
+    marked.sort();
+    let mut running = cluster.nodes().map(|node| node.addr()).collect::<Vec<_>>();
+    running.sort();
+    assert_eq!(marked, running);
+    for node in cluster.nodes() {
+        assert!(
+            !node.in_roll_call(),
+            "{} is still in the roll call",
+            node.addr()
+        );
+    }
+}
+
+#[test]
+fn roll_call_marks_every_node() {
+    let mut cluster = Cluster::start(4).unwrap();
+    assert_roll_call(&mut cluster, 0);
+    assert_roll_call(&mut cluster, 2);
+}
+
+#[test]
+fn roll_call_routes_around_a_killed_node() {
+    let mut cluster = Cluster::start(4).unwrap();
+    cluster.kill(2).unwrap();
+    assert_roll_call(&mut cluster, 0);
+    assert_roll_call(&mut cluster, 3);
+}
+
+#[test]
+fn roll_call_routes_around_a_hung_node() {
+    let mut cluster = Cluster::start(4).unwrap();
+    let mut addresses = cluster.addresses().to_vec();
+    // The roll call reaches it before it's found down, so the message to it times out
+    cluster.set_fault(2, Some(Fault::Hung));
+    let tally = cluster.roll_call(0, HUNG_DEADLINE).unwrap();
+
+    let mut marked = tally
+        .get_marked()
+        .iter()
+        .map(|node| node.parse().unwrap())
+        .collect::<Vec<SocketAddr>>();
+    marked.sort();
+    addresses.remove(2);
+    addresses.sort();
+    assert_eq!(marked, addresses);
+}
+
+#[test]
+fn restarted_node_is_marked_again() {
+    let mut cluster = Cluster::start(4).unwrap();
+    cluster.kill(1).unwrap();
+    assert_roll_call(&mut cluster, 0);
+    cluster.restart(1).unwrap();
+    assert_roll_call(&mut cluster, 1);
+    assert_roll_call(&mut cluster, 3);
+}
+
+#[test]
+fn status_and_events_follow_a_roll_call() {
+    let mut cluster = Cluster::start(3).unwrap();
+    let addresses = cluster.addresses().to_vec();
+    let remote = cluster.connect(0).unwrap();
+    let events = remote.watch_events();
+    // Gives the stream time to be set up before anything happens
+    thread::sleep(Duration::from_millis(500));
+    let tally = cluster.roll_call(0, DEADLINE).unwrap();
+
+    let mut runtime = Runtime::new().unwrap();
+    let status = runtime.block_on(remote.status()).unwrap();
+    assert_eq!(status.get_address(), addresses[0].to_string());
+    assert_eq!(status.get_successor(), addresses[1].to_string());
+    assert!(!status.get_in_roll_call());
+    assert!(status.get_roll_calls().is_empty());
+    let handled = |action: &str| {
+        status
+            .get_handled()
+            .iter()
+            .find(|counter| counter.get_action() == action)
+            .map(|counter| counter.get_count())
+    };
+    // Started by the client, then back from the ring, then the tally
+    assert_eq!(handled("StartRollCall"), Some(2));
+    assert_eq!(handled("MarkItself"), Some(1));
+
+    // The stream ends once the node is killed
+    cluster.kill(0).unwrap();
+    let events = events
+        .wait()
+        .take_while(Result::is_ok)
+        .map(Result::unwrap)
+        .filter(|event| event.get_detail() == tally.get_id())
+        .map(|event| event.get_kind().to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(events, ["ON", "OFF", "TALLY"]);
+}
@@ -0,0 +1,158 @@
+use grpc_microservice::cluster::Cluster;
+use grpc_microservice::tls::TlsConfig;
+use grpc_microservice::Remote;
+use openssl::asn1::Asn1Time;
+use openssl::bn::BigNum;
+use openssl::error::ErrorStack;
+use openssl::hash::MessageDigest;
+use openssl::pkey::{PKey, Private};
+use openssl::rsa::Rsa;
+use openssl::x509::extension::{
+    BasicConstraints, ExtendedKeyUsage, KeyUsage, SubjectAlternativeName,
+};
+use openssl::x509::{X509NameBuilder, X509};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+const DEADLINE: Duration = Duration::from_secs(10);
+
+fn key() -> Result<PKey<Private>, ErrorStack> {
+    PKey::from_rsa(Rsa::generate(2048)?)
+}
+
+/// A certificate for `name`, self-signed CA without an issuer
+fn certificate(
+    name: &str,
+    key: &PKey<Private>,
+    issuer: Option<(&X509, &PKey<Private>)>,
+) -> Result<X509, ErrorStack> {
+    let mut subject = X509NameBuilder::new()?;
+    subject.append_entry_by_text("CN", name)?;
+    let subject = subject.build();
+
+    let mut builder = X509::builder()?;
+    builder.set_version(2)?;
+    builder.set_serial_number(&*BigNum::from_u32(rand_serial())?.to_asn1_integer()?)?;
+    builder.set_subject_name(&subject)?;
+    builder.set_pubkey(key)?;
+    builder.set_not_before(&*Asn1Time::days_from_now(0)?)?;
+    builder.set_not_after(&*Asn1Time::days_from_now(1)?)?;
+    match issuer {
+        None => {
+            builder.set_issuer_name(&subject)?;
+            builder.append_extension(BasicConstraints::new().critical().ca().build()?)?;
+            let usage = KeyUsage::new()
+                .critical()
+                .key_cert_sign()
+                .crl_sign()
+                .build()?;
+            builder.append_extension(usage)?;
+            builder.sign(key, MessageDigest::sha256())?;
+        }
+        Some((ca, ca_key)) => {
+            builder.set_issuer_name(ca.subject_name())?;
+            let usage = KeyUsage::new()
+                .digital_signature()
+                .key_encipherment()
+                .build()?;
+            builder.append_extension(usage)?;
+            let usage = ExtendedKeyUsage::new()
+                .server_auth()
+                .client_auth()
+                .build()?;
+            builder.append_extension(usage)?;
+            let names = SubjectAlternativeName::new()
+                .ip("127.0.0.1")
+                .build(&builder.x509v3_context(Some(ca), None))?;
+            builder.append_extension(names)?;
+            builder.sign(ca_key, MessageDigest::sha256())?;
+        }
+    }
+    Ok(builder.build())
+}
+
+fn rand_serial() -> u32 {
+    rand::random::<u32>() >> 1
+}
+
+/// A CA and a certificate it signed for 127.0.0.1, written as PEM files under `name`
+struct Certificates {
+    dir: PathBuf,
+}
+
+impl Certificates {
+    fn generate(name: &str) -> Self {
+        let dir = env::temp_dir().join(format!("grpc-ring-{}-{}", name, process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let ca_key = key().unwrap();
+        let ca = certificate(&format!("{} CA", name), &ca_key, None).unwrap();
+        let node_key = key().unwrap();
+        let node = certificate("ring node", &node_key, Some((&ca, &ca_key))).unwrap();
+
+        fs::write(dir.join("ca.pem"), ca.to_pem().unwrap()).unwrap();
+        fs::write(dir.join("node.pem"), node.to_pem().unwrap()).unwrap();
+        let key = node_key.private_key_to_pem_pkcs8().unwrap();
+        fs::write(dir.join("node.key"), key).unwrap();
+        Self { dir }
+    }
+
+    /// Presents this node certificate, trusting the CA of `trusted`
+    fn config(&self, trusted: &Certificates) -> TlsConfig {
+        TlsConfig {
+            cert: self.dir.join("node.pem"),
+            key: self.dir.join("node.key"),
+            ca: trusted.dir.join("ca.pem"),
+            domain: None,
+        }
+    }
+}
+
+impl Drop for Certificates {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[test]
+fn roll_call_over_mutual_tls() {
+    let certificates = Certificates::generate("mutual");
+    let config = certificates.config(&certificates);
+    let mut cluster = Cluster::start_with_tls(3, Some(config)).unwrap();
+
+    let tally = cluster.roll_call(0, DEADLINE).unwrap();
+    assert_eq!(tally.get_marked().len(), 3);
+}
+
+#[test]
+fn plain_client_is_refused() {
+    let certificates = Certificates::generate("plain");
+    let config = certificates.config(&certificates);
+    let cluster = Cluster::start_with_tls(1, Some(config)).unwrap();
+    let mut runtime = Runtime::new().unwrap();
+
+    let remote = Remote::new(cluster.addresses()[0]).unwrap();
+    assert!(runtime.block_on(remote.status()).is_err());
+}
+
+#[test]
+fn client_certificate_from_another_ca_is_refused() {
+    let certificates = Certificates::generate("trusted");
+    let rogue = Certificates::generate("rogue");
+    let config = certificates.config(&certificates);
+    let cluster = Cluster::start_with_tls(1, Some(config)).unwrap();
+    let mut runtime = Runtime::new().unwrap();
+
+    // The client trusts the server, the server doesn't trust the client
+    let tls = rogue.config(&certificates).client().unwrap();
+    let remote = Remote::connect(cluster.addresses()[0], Some(&tls)).unwrap();
+    assert!(runtime.block_on(remote.status()).is_err());
+
+    let tls = certificates.config(&certificates).client().unwrap();
+    let remote = Remote::connect(cluster.addresses()[0], Some(&tls)).unwrap();
+    assert!(runtime.block_on(remote.status()).is_ok());
+}
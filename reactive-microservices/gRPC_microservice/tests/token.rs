@@ -0,0 +1,150 @@
+use grpc_microservice::cluster::Cluster;
+use grpc_microservice::ring::Lock;
+use grpc_microservice::Remote;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+/// Long enough for the leader to find the token lost and regenerate it
+const DEADLINE: Duration = Duration::from_secs(30);
+/// Every token passed to a slow node waits for the deadline of each attempt
+const SLOW_DEADLINE: Duration = Duration::from_secs(90);
+const CLIENTS_PER_NODE: usize = 2;
+const ROUNDS: usize = 5;
+
+/// Runs clients on every node that each take the lock `ROUNDS` times, checking no two hold
+/// it at once
+fn assert_one_holder_at_a_time(cluster: &Cluster, clients_per_node: usize, deadline: Duration) {
+    let holders = Arc::new(AtomicUsize::new(0));
+    let granted = Arc::new(AtomicUsize::new(0));
+    let mut clients = Vec::new();
+    for &addr in cluster.addresses() {
+        for _ in 0..clients_per_node {
+            let holders = holders.clone();
+            let granted = granted.clone();
+            clients.push(thread::spawn(move || {
+                let remote = Remote::new(addr).unwrap().with_deadline(deadline);
+                let mut runtime = Runtime::new().unwrap();
+                for _ in 0..ROUNDS {
+                    let lock = runtime.block_on(remote.acquire()).unwrap();
+                    assert_eq!(
+                        holders.fetch_add(1, Ordering::SeqCst),
+                        0,
+                        "two holders at once"
+                    );
+                    granted.fetch_add(1, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(20));
+                    holders.fetch_sub(1, Ordering::SeqCst);
+                    runtime.block_on(remote.release(lock)).unwrap();
+                }
+            }));
+        }
+    }
+    for client in clients {
+        client.join().unwrap();
+    }
+    let expected = cluster.addresses().len() * clients_per_node * ROUNDS;
+    assert_eq!(granted.load(Ordering::SeqCst), expected);
+}
+
+#[test]
+fn one_holder_at_a_time() {
+    let mut cluster = Cluster::start(4).unwrap();
+    cluster.elect(0).unwrap();
+    assert_one_holder_at_a_time(&cluster, CLIENTS_PER_NODE, DEADLINE);
+}
+
+#[test]
+fn first_acquire_elects_a_leader() {
+    let cluster = Cluster::start(3).unwrap();
+    let remote = Remote::new(cluster.addresses()[1])
+        .unwrap()
+        .with_deadline(DEADLINE);
+    let mut runtime = Runtime::new().unwrap();
+
+    let lock = runtime.block_on(remote.acquire()).unwrap();
+    runtime.block_on(remote.release(lock)).unwrap();
+    assert!(cluster.nodes().all(|node| node.leader().is_some()));
+    assert_one_holder_at_a_time(&cluster, 1, DEADLINE);
+}
+
+#[test]
+fn holder_stays_until_the_lock_is_released() {
+    let mut cluster = Cluster::start(3).unwrap();
+    cluster.elect(0).unwrap();
+    let addr = cluster.addresses()[1];
+    let remote = Remote::new(addr).unwrap().with_deadline(DEADLINE);
+    let mut runtime = Runtime::new().unwrap();
+
+    let lock = runtime.block_on(remote.acquire()).unwrap();
+    assert!(runtime.block_on(remote.leave(addr, None)).is_err());
+    runtime.block_on(remote.release(lock)).unwrap();
+    runtime.block_on(remote.leave(addr, None)).unwrap();
+}
+
+#[test]
+fn slow_successor_does_not_duplicate_the_token() {
+    // Slower than the default deadline, so that every message to the node times out although
+    // the node took it
+    let mut cluster = Cluster::start_with_slow_node(3, 1, Duration::from_millis(2500)).unwrap();
+    cluster.elect(0).unwrap();
+    assert_one_holder_at_a_time(&cluster, 1, SLOW_DEADLINE);
+}
+
+#[test]
+fn lock_not_held_is_not_released() {
+    let mut cluster = Cluster::start(3).unwrap();
+    cluster.elect(0).unwrap();
+    let remote = Remote::new(cluster.addresses()[1])
+        .unwrap()
+        .with_deadline(DEADLINE);
+    let mut runtime = Runtime::new().unwrap();
+
+    let lock = runtime.block_on(remote.acquire()).unwrap();
+    let mut forged = Lock::new();
+    forged.set_id("forged".to_owned());
+    assert!(runtime.block_on(remote.release(forged)).is_err());
+    runtime.block_on(remote.release(lock.clone())).unwrap();
+    assert!(runtime.block_on(remote.release(lock)).is_err());
+}
+
+#[test]
+fn token_is_regenerated_when_its_holder_dies() {
+    let mut cluster = Cluster::start(4).unwrap();
+    let leader = cluster.elect(0).unwrap();
+    let addresses = cluster.addresses().to_vec();
+    let holder = addresses.iter().position(|&addr| addr != leader).unwrap();
+    let other = (0..addresses.len()).find(|&index| index != holder).unwrap();
+    let mut runtime = Runtime::new().unwrap();
+
+    let remote = Remote::new(addresses[holder])
+        .unwrap()
+        .with_deadline(DEADLINE);
+    runtime.block_on(remote.acquire()).unwrap();
+    cluster.kill(holder).unwrap();
+
+    let remote = Remote::new(addresses[other])
+        .unwrap()
+        .with_deadline(DEADLINE);
+    let lock = runtime.block_on(remote.acquire()).unwrap();
+    runtime.block_on(remote.release(lock)).unwrap();
+}
+
+#[test]
+fn lock_never_released_expires() {
+    let mut cluster = Cluster::start(3).unwrap();
+    cluster.elect(0).unwrap();
+    let addresses = cluster.addresses().to_vec();
+    let mut runtime = Runtime::new().unwrap();
+
+    let forgetful = Remote::new(addresses[1]).unwrap().with_deadline(DEADLINE);
+    let forgotten = runtime.block_on(forgetful.acquire()).unwrap();
+    assert!(forgotten.get_lease_secs() > 0);
+
+    let remote = Remote::new(addresses[2]).unwrap().with_deadline(DEADLINE);
+    let lock = runtime.block_on(remote.acquire()).unwrap();
+    runtime.block_on(remote.release(lock)).unwrap();
+    assert!(runtime.block_on(forgetful.release(forgotten)).is_err());
+}
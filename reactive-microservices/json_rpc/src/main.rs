@@ -1,29 +1,143 @@
-use failure::Error;
-use jsonrpc::client::Client;
-use jsonrpc::error::Error as ClientError;
-use jsonrpc_http_server::jsonrpc_core::{Error as ServerError, IoHandler, Value};
+use failure::{Error, Fail};
+use hyper::header::ContentType;
+use hyper::Client;
+use jsonrpc_http_server::jsonrpc_core;
+use jsonrpc_http_server::jsonrpc_core::{
+    Call, Error as ServerError, Id, IoHandler, MethodCall, Notification, Output, Params, Value,
+    Version,
+};
 use jsonrpc_http_server::ServerBuilder;
-use log::{debug, error, trace};
-use serde::Deserialize;
+use log::{debug, error, info, trace, warn};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::env;
 use std::fmt;
 use std::fmt::Display;
+use std::io;
+use std::iter;
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc::{channel, Sender};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::Duration;
 
 // To avoid mistakes in method names, declare them as string constants
+/// Called by clients, answers with the roll call started
 const START_ROLL_CALL: &str = "start_roll_call";
+/// The roll call going around the ring, as a notification
+const PASS_ROLL_CALL: &str = "pass_roll_call";
 const MARK_ITSELF: &str = "mark_itself";
 
+/// How often a batch is sent before it's given up, only batches the next node refused to
+/// take are tried again
+const FORWARD_ATTEMPTS: u32 = 3;
+const FORWARD_BACKOFF: Duration = Duration::from_millis(200);
+
+/// The params of both methods, a roll call with no id is a new one started by a client
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct RollCall {
+    id: String,
+    initiator: String,
+    visited: Vec<String>,
+}
+
+impl RollCall {
+    // Sent as a single positional param
+    fn params(&self) -> Params {
+        Params::Array(vec![serde_json::json!(self)])
+    }
+
+    // Accepts the roll call as a single positional param, by name or not at all
+    fn parse(params: Params) -> Result<Self, ServerError> {
+        match params {
+            Params::None => Ok(Self::default()),
+            Params::Array(_) => params.parse::<(Self,)>().map(|(call,)| call),
+            Params::Map(_) => params.parse(),
+        }
+    }
+}
+
 struct Remote {
     client: Client,
+    url: String,
+}
+
+/// One call for `Remote::call_method`, notifications get no response
+struct Request {
+    method: &'static str,
+    params: Params,
+    notification: bool,
+}
+
+impl Request {
+    fn method(method: &'static str, call: &RollCall) -> Self {
+        Self {
+            method,
+            params: call.params(),
+            notification: false,
+        }
+    }
+
+    fn notification(method: &'static str, call: &RollCall) -> Self {
+        Self {
+            notification: true,
+            ..Self::method(method, call)
+        }
+    }
+
+    // The index in the batch is used as the id
+    fn to_call(&self, index: usize) -> Call {
+        let jsonrpc = Some(Version::V2);
+        let method = self.method.to_owned();
+        let params = self.params.clone();
+        if self.notification {
+            Call::Notification(Notification {
+                jsonrpc,
+                method,
+                params,
+            })
+        } else {
+            Call::MethodCall(MethodCall {
+                jsonrpc,
+                method,
+                params,
+                id: Id::Num(index as u64),
+            })
+        }
+    }
+}
+
+#[derive(Debug, Fail)]
+enum RemoteError {
+    #[fail(display = "request failed: {}", _0)]
+    Http(#[cause] hyper::Error),
+    #[fail(display = "bad JSON: {}", _0)]
+    Json(#[cause] serde_json::Error),
+    #[fail(display = "server replied with status {}", _0)]
+    Status(u16),
+    #[fail(display = "call failed with {}: {}", code, message)]
+    Rpc { code: i64, message: String },
+    #[fail(display = "no response to call {}", _0)]
+    NoResponse(usize),
+}
+
+impl From<hyper::Error> for RemoteError {
+    fn from(err: hyper::Error) -> Self {
+        RemoteError::Http(err)
+    }
+}
+
+impl From<serde_json::Error> for RemoteError {
+    fn from(err: serde_json::Error) -> Self {
+        RemoteError::Json(err)
+    }
 }
 
 enum Action {
-    StartRollCall,
-    MarkItself,
+    StartRollCall(RollCall),
+    MarkItself(RollCall),
 }
 //
 //impl Display for Action {
@@ -38,86 +152,247 @@ enum Action {
 impl Remote {
     fn new(addr: SocketAddr) -> Self {
         let url = format!("http://{}", addr);
-        let client = Client::new(url, None, None);
-        Self { client }
+        let client = Client::new();
+        Self { client, url }
     }
 
-    // Used to pre-load a request and send it with the client
-    fn call_method<T>(&self, method: &str, args: &[Value]) -> Result<T, ClientError>
-    where
-        T: for<'de> Deserialize<'de>,
-    {
-        let request = self.client.build_request(method, args);
-        self.client
-            .send_request(&request)
-            .and_then(|res| res.into_result::<T>())
+    // Sends the requests in one HTTP round trip, as a batch if there are several, and returns
+    // the results of the method calls in order
+    fn call_method(&self, requests: &[Request]) -> Result<Vec<Value>, RemoteError> {
+        let mut calls = requests
+            .iter()
+            .enumerate()
+            .map(|(index, request)| request.to_call(index))
+            .collect::<Vec<_>>();
+        let request = if calls.len() == 1 {
+            jsonrpc_core::Request::Single(calls.remove(0))
+        } else {
+            jsonrpc_core::Request::Batch(calls)
+        };
+        let body = serde_json::to_string(&request)?;
+        let response = self
+            .client
+            .post(&self.url)
+            .header(ContentType::json())
+            .body(body.as_str())
+            .send()?;
+        if !response.status.is_success() {
+            return Err(RemoteError::Status(response.status.to_u16()));
+        }
+
+        // Nothing comes back when every request is a notification
+        if requests.iter().all(|request| request.notification) {
+            return Ok(Vec::new());
+        }
+        let outputs = match serde_json::from_reader(response)? {
+            jsonrpc_core::Response::Single(output) => vec![output],
+            jsonrpc_core::Response::Batch(outputs) => outputs,
+        };
+        match_outputs(requests, outputs)
     }
 
     //  Business Logic
-    fn start_roll_call(&self) -> Result<bool, ClientError> {
-        self.call_method(START_ROLL_CALL, &[])
+    fn start_roll_call(&self) -> Result<RollCall, RemoteError> {
+        let request = Request::method(START_ROLL_CALL, &RollCall::default());
+        let value = self.call_method(&[request])?.remove(0);
+        Ok(serde_json::from_value(value)?)
     }
+}
 
-    fn mark_itself(&self) -> Result<bool, ClientError> {
-        self.call_method(MARK_ITSELF, &[])
+/// Picks the result of every method call out of the outputs, which may come in any order
+fn match_outputs(requests: &[Request], outputs: Vec<Output>) -> Result<Vec<Value>, RemoteError> {
+    let mut results = outputs
+        .into_iter()
+        .map(|output| match output {
+            Output::Success(success) => (success.id, Ok(success.result)),
+            Output::Failure(failure) => (failure.id, Err(failure.error)),
+        })
+        .collect::<Vec<_>>();
+
+    let mut values = Vec::new();
+    for (index, _) in requests.iter().enumerate().filter(|(_, r)| !r.notification) {
+        let id = Id::Num(index as u64);
+        let position = results.iter().position(|(other, _)| *other == id);
+        let (_, result) = results.swap_remove(position.ok_or(RemoteError::NoResponse(index))?);
+        values.push(result.map_err(|err| RemoteError::Rpc {
+            code: err.code.code(),
+            message: err.message,
+        })?);
     }
+    Ok(values)
+}
+
+// Marks this node as visited, the roll call then goes on to mark the others
+fn mark_itself(addr: &str, mut call: RollCall) -> Request {
+    call.visited.push(addr.to_owned());
+    Request::notification(MARK_ITSELF, &call)
 }
 
-fn spawn_worker() -> Result<Sender<Action>, Error> {
+fn spawn_worker(addr: SocketAddr) -> Result<Sender<Action>, Error> {
     let (tx, rx) = channel();
     let next: SocketAddr = env::var("NEXT")?.parse()?;
+    let addr = addr.to_string();
 
     thread::spawn(move || {
         let remote = Remote::new(next);
-        let mut in_roll_call = false;
-
-        for action in rx.iter() {
-            match action {
-                Action::StartRollCall => {
-                    if !in_roll_call && remote.start_roll_call().is_ok() {
-                        debug!("ON");
-                        in_roll_call = true;
-                    } else if in_roll_call && remote.mark_itself().is_ok() {
-                        debug!("OFF");
-                        in_roll_call = false;
+        // The ids of the roll calls this node is in
+        let mut roll_calls = HashSet::new();
+
+        // Handles whatever is queued up and forwards it all in one batch of notifications
+        while let Ok(action) = rx.recv() {
+            let mut requests = Vec::new();
+            let mut ids = Vec::new();
+            for action in iter::once(action).chain(rx.try_iter()) {
+                match action {
+                    Action::StartRollCall(call) => {
+                        if roll_calls.insert(call.id.clone()) {
+                            debug!("ON {}", call.id);
+                            ids.push(call.id.clone());
+                            requests.push(Request::notification(PASS_ROLL_CALL, &call));
+                        } else if call.initiator == addr {
+                            // Back at the initiator, the marking round starts
+                            debug!("OFF {}", call.id);
+                            roll_calls.remove(&call.id);
+                            ids.push(call.id.clone());
+                            requests.push(mark_itself(&addr, call));
+                        } else {
+                            // Passed on here already, a copy would start a second marking round
+                            debug!("DUPLICATE {}", call.id);
+                        }
                     }
-                }
-                Action::MarkItself => {
-                    if in_roll_call && remote.mark_itself().is_ok() {
-                        debug!("OFF");
-                        in_roll_call = false;
-                    } else {
-                        debug!("SKIP");
+                    Action::MarkItself(call) => {
+                        if roll_calls.remove(&call.id) {
+                            debug!("OFF {}", call.id);
+                            ids.push(call.id.clone());
+                            requests.push(mark_itself(&addr, call));
+                        } else if call.initiator == addr {
+                            info!("TALLY {}: {}", call.id, call.visited.join(", "));
+                        } else if call.visited.contains(&addr) {
+                            // Marked here already and around the ring again, the initiator is gone
+                            debug!("DROP {}", call.id);
+                        } else {
+                            // Not in this roll call, it started before the node joined
+                            debug!("SKIP {}", call.id);
+                            ids.push(call.id.clone());
+                            requests.push(Request::notification(MARK_ITSELF, &call));
+                        }
                     }
                 }
             }
+            if !requests.is_empty() {
+                forward(&remote, &requests, &ids);
+            }
         }
     });
     Ok(tx)
 }
 
+/// Sends the batch to the next node, again if it couldn't be reached. The roll calls in a
+/// batch given up are reported, they won't get a tally
+fn forward(remote: &Remote, requests: &[Request], ids: &[String]) {
+    for attempt in 1..=FORWARD_ATTEMPTS {
+        match remote.call_method(requests) {
+            Ok(_) => return,
+            // Only a batch the next node never took is sent again, one that failed after that
+            // may have been handled there
+            Err(RemoteError::Http(ref err)) if never_sent(err) && attempt < FORWARD_ATTEMPTS => {
+                warn!("Can't reach {}, trying again: {}", remote.url, err);
+                thread::sleep(FORWARD_BACKOFF * attempt);
+            }
+            Err(err) => {
+                error!(
+                    "Can't forward roll calls {} to {}: {}",
+                    ids.join(", "),
+                    remote.url,
+                    err
+                );
+                return;
+            }
+        }
+    }
+}
+
+/// Whether the request failed before the next node could read any of it, the connection
+/// was refused
+fn never_sent(err: &hyper::Error) -> bool {
+    match *err {
+        hyper::Error::Io(ref err) => err.kind() == io::ErrorKind::ConnectionRefused,
+        _ => false,
+    }
+}
+
+struct Handler {
+    sender: Mutex<Sender<Action>>,
+    addr: String,
+    roll_calls: AtomicUsize,
+}
+
+impl Handler {
+    fn start_roll_call(&self, params: Params) -> Result<RollCall, ServerError> {
+        trace!("START_ROLL_CALL");
+        let mut call = RollCall::parse(params)?;
+        if call.id.is_empty() {
+            let number = self.roll_calls.fetch_add(1, Ordering::SeqCst);
+            call.id = format!("{}-{}", self.addr, number);
+            call.initiator = self.addr.clone();
+        }
+        self.send(Action::StartRollCall(call.clone()))?;
+        Ok(call)
+    }
+
+    fn mark_itself(&self, params: Params) -> Result<(), ServerError> {
+        trace!("MARK_ITSELF");
+        let call = RollCall::parse(params)?;
+        self.send(Action::MarkItself(call))
+    }
+
+    fn send(&self, action: Action) -> Result<(), ServerError> {
+        let tx = self.sender.lock().map_err(to_internal)?;
+        tx.send(action).map_err(to_internal)
+    }
+}
+
 fn main() -> Result<(), Error> {
     env_logger::init();
-    let tx = spawn_worker()?;
+
+    // `json_rpc start` asks NEXT to start a roll call and prints its id
+    if env::args().nth(1).as_deref() == Some("start") {
+        let next: SocketAddr = env::var("NEXT")?.parse()?;
+        let call = Remote::new(next).start_roll_call()?;
+        println!("{}", call.id);
+        return Ok(());
+    }
+
     let addr: SocketAddr = env::var("ADDRESS")?.parse()?;
-    let mut io = IoHandler::default();
+    let tx = spawn_worker(addr)?;
+    let mut io = IoHandler::new();
 
-    let sender = Mutex::new(tx.clone());
-    io.add_method(START_ROLL_CALL, move |_| {
-        trace!("START_ROLL_CALL");
-        let tx = sender.lock().map_err(to_internal)?;
-        tx.send(Action::StartRollCall)
-            .map_err(to_internal)
-            .map(|_| Value::Bool(true))
+    let handler = Arc::new(Handler {
+        sender: Mutex::new(tx),
+        addr: addr.to_string(),
+        roll_calls: AtomicUsize::new(0),
     });
 
-    let sender = Mutex::new(tx.clone());
-    io.add_method(MARK_ITSELF, move |_| {
-        trace!("MARK_ITSELF");
-        let tx = sender.lock().map_err(to_internal)?;
-        tx.send(Action::MarkItself)
-            .map_err(to_internal)
-            .map(|_| Value::Bool(true))
+    // Clients start a roll call with a method call to learn its id, nodes pass it on with a
+    // notification, both do the same
+    let h = handler.clone();
+    io.add_method(START_ROLL_CALL, move |params| {
+        h.start_roll_call(params)
+            .and_then(|call| serde_json::to_value(call).map_err(to_internal))
+    });
+
+    let h = handler.clone();
+    io.add_notification(PASS_ROLL_CALL, move |params| {
+        if let Err(err) = h.start_roll_call(params) {
+            warn!("Bad {} notification: {}", PASS_ROLL_CALL, err.message);
+        }
+    });
+
+    let h = handler;
+    io.add_notification(MARK_ITSELF, move |params| {
+        if let Err(err) = h.mark_itself(params) {
+            warn!("Bad {} notification: {}", MARK_ITSELF, err.message);
+        }
     });
 
     let server = ServerBuilder::new(io).start_http(&addr)?;
@@ -128,3 +403,106 @@ fn to_internal<E: fmt::Display>(err: E) -> ServerError {
     error!("Error: {}", err);
     ServerError::internal_error()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Read;
+    use std::net::TcpListener;
+
+    fn output(value: Value) -> Output {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn roll_call_params() {
+        let call = RollCall::parse(Params::None).unwrap();
+        assert!(call.id.is_empty() && call.visited.is_empty());
+
+        let array = json!([{"id": "a-1", "initiator": "a", "visited": ["a", "b"]}]);
+        let call = RollCall::parse(serde_json::from_value(array).unwrap()).unwrap();
+        assert_eq!(call.id, "a-1");
+        assert_eq!(call.initiator, "a");
+        assert_eq!(call.visited, ["a", "b"]);
+
+        let map = json!({"id": "a-2"});
+        let call = RollCall::parse(serde_json::from_value(map).unwrap()).unwrap();
+        assert_eq!(call.id, "a-2");
+        assert!(call.initiator.is_empty());
+
+        // The params sent are taken back as they were
+        let sent = RollCall::parse(call.params()).unwrap();
+        assert_eq!(sent.id, call.id);
+
+        for bad in &[json!([1]), json!({"visited": "a"}), json!([{}, {}])] {
+            let params = serde_json::from_value(bad.clone()).unwrap();
+            assert!(RollCall::parse(params).is_err(), "{} was taken", bad);
+        }
+    }
+
+    #[test]
+    fn outputs_are_matched_to_calls_by_id() {
+        let call = RollCall::default();
+        let requests = [
+            Request::method(START_ROLL_CALL, &call),
+            Request::notification(MARK_ITSELF, &call),
+            Request::method(START_ROLL_CALL, &call),
+        ];
+        // Out of order, notifications have no output
+        let outputs = vec![
+            output(json!({"jsonrpc": "2.0", "result": "third", "id": 2})),
+            output(json!({"jsonrpc": "2.0", "result": "first", "id": 0})),
+        ];
+        let values = match_outputs(&requests, outputs).unwrap();
+        assert_eq!(values, [json!("first"), json!("third")]);
+    }
+
+    #[test]
+    fn missing_and_failed_outputs_are_errors() {
+        let call = RollCall::default();
+        let requests = [
+            Request::method(START_ROLL_CALL, &call),
+            Request::method(START_ROLL_CALL, &call),
+        ];
+        let outputs = vec![output(json!({"jsonrpc": "2.0", "result": true, "id": 0}))];
+        match match_outputs(&requests, outputs) {
+            Err(RemoteError::NoResponse(1)) => {}
+            other => panic!("expected no response to call 1, got {:?}", other),
+        }
+
+        let failure = json!({
+            "jsonrpc": "2.0",
+            "error": {"code": -32602, "message": "Invalid params"},
+            "id": 0,
+        });
+        match match_outputs(&requests[..1], vec![output(failure)]) {
+            Err(RemoteError::Rpc { code: -32602, .. }) => {}
+            other => panic!("expected the call to fail, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn only_refused_batches_count_as_never_sent() {
+        let requests = [Request::notification(MARK_ITSELF, &RollCall::default())];
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // Taken and dropped unanswered, the node may have handled it
+        let node = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let _ = stream.read(&mut [0; 1024]);
+        });
+        match Remote::new(addr).call_method(&requests) {
+            Err(RemoteError::Http(ref err)) => assert!(!never_sent(err), "{}", err),
+            other => panic!("expected the request to fail, got {:?}", other),
+        }
+        node.join().unwrap();
+
+        // Nothing listens any more
+        match Remote::new(addr).call_method(&requests) {
+            Err(RemoteError::Http(ref err)) => assert!(never_sent(err), "{}", err),
+            other => panic!("expected the request to fail, got {:?}", other),
+        }
+    }
+}